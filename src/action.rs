@@ -1,8 +1,18 @@
+use std::{collections::BTreeMap, ops::Range, path::PathBuf};
+
+use crossterm::event::KeyEvent;
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use serde_json::Value;
+use strum::{Display, EnumIter};
+
+use crate::app::Mode;
+use crate::interpret::{Encoding, Endianness, Kind};
+use crate::layout::Panel;
+use crate::recovery::SessionState;
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
 pub enum CharacterInput {
+    #[default]
     Zero,
     One,
     Two,
@@ -22,7 +32,40 @@ pub enum CharacterInput {
     Dot,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+impl CharacterInput {
+    /// Numeric value of the input, `None` for [`CharacterInput::Dot`].
+    pub fn digit(&self) -> Option<u8> {
+        let digit = match self {
+            CharacterInput::Zero => 0,
+            CharacterInput::One => 1,
+            CharacterInput::Two => 2,
+            CharacterInput::Three => 3,
+            CharacterInput::Four => 4,
+            CharacterInput::Five => 5,
+            CharacterInput::Six => 6,
+            CharacterInput::Seven => 7,
+            CharacterInput::Eight => 8,
+            CharacterInput::Nine => 9,
+            CharacterInput::Ten => 10,
+            CharacterInput::Eleven => 11,
+            CharacterInput::Twelve => 12,
+            CharacterInput::Thirteen => 13,
+            CharacterInput::Fourteen => 14,
+            CharacterInput::Fifteen => 15,
+            CharacterInput::Dot => return None,
+        };
+        Some(digit)
+    }
+}
+
+/// A named offset into the buffer.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
 pub enum Action {
     Tick,
     Render,
@@ -32,11 +75,139 @@ pub enum Action {
     Quit,
     ClearScreen,
     Error(String),
+    /// A message for the status bar.
+    Info(String),
     Help,
+    SwitchMode(Mode),
+    FocusNext,
+    FocusPrevious,
+    Submit,
 
     ScrollDown,
     ScrollUp,
     SelectRight,
     SelectLeft,
     CharacterInput(CharacterInput),
+    DeleteCharacter,
+
+    /// The current byte buffer has been replaced.
+    SetBuffer(Vec<u8>),
+    ToggleExport,
+    ToggleChecksums,
+    /// Show or hide the latest log records.
+    ToggleLogs,
+    CommandPalette,
+    ReloadConfig,
+
+    /// Numeric type the buffer is read as.
+    SetKind(Kind),
+    SetEndianness(Endianness),
+    /// Text encoding the buffer is read as.
+    SetEncoding(Encoding),
+
+    /// Bookmark the offset shown by the focused panel.
+    AddBookmark,
+    /// A bookmark has been saved.
+    Bookmarked(Bookmark),
+    /// The bookmarks have been replaced, e.g. by loading a workspace.
+    SetBookmarks(Vec<Bookmark>),
+    /// Scroll to a byte offset of the buffer.
+    GoTo(usize),
+    /// The offset the hex view points at moved: the end of the selection or the top line.
+    SetCursor(usize),
+    /// The bytes selected in the hex view changed, `None` once nothing is selected.
+    SetSelection(Option<Range<usize>>),
+    /// Keys typed so far of a multi-key binding, empty once it is completed or abandoned.
+    PendingKeys(Vec<KeyEvent>),
+    /// Use one of the built-in themes.
+    SetTheme(String),
+    /// Ask whether to restore a session that did not end normally.
+    OfferRestore(SessionState),
+    /// Bring back the buffer and settings of an earlier session.
+    RestoreSession(SessionState),
+    /// Save the workspace under a name, or to a path.
+    SaveWorkspace(String),
+    /// Compare the buffer with the bytes of a file.
+    CompareWith(PathBuf),
+    /// The buffer the current one is compared with has been replaced, by the bytes of a file if
+    /// one is given.
+    SetCompareBuffer(Vec<u8>, Option<PathBuf>),
+    /// Load the workspace saved under a name, or at a path.
+    LoadWorkspace(String),
+    /// Hand every panel the selection saved with a workspace.
+    RestoreSelections(BTreeMap<Panel, Value>),
+}
+
+impl Action {
+    /// Actions triggered by user input that only concern the components shown in the active
+    /// [`Mode`], every other action is seen by all components.
+    pub fn is_targeted(&self) -> bool {
+        matches!(
+            self,
+            Action::ScrollDown
+                | Action::ScrollUp
+                | Action::SelectRight
+                | Action::SelectLeft
+                | Action::CharacterInput(_)
+                | Action::DeleteCharacter
+                | Action::Submit
+                | Action::AddBookmark
+        )
+    }
+
+    /// What the action does, as shown in the help overlay.
+    pub fn description(&self) -> String {
+        let description = match self {
+            Action::Quit => "Quit the application",
+            Action::Suspend => "Suspend the application",
+            Action::ClearScreen => "Redraw the screen",
+            Action::Help => "Show or hide this help",
+            Action::SwitchMode(mode) => return format!("Switch to the {mode} mode"),
+            Action::FocusNext => "Focus the next panel",
+            Action::FocusPrevious => "Focus the previous panel",
+            Action::Submit => "Apply the selection",
+            Action::ScrollDown => "Move down",
+            Action::ScrollUp => "Move up",
+            Action::SelectRight => "Select the next option",
+            Action::SelectLeft => "Select the previous option",
+            Action::CharacterInput(CharacterInput::Dot) => "Type a dot",
+            Action::CharacterInput(_) => "Type a hex digit",
+            Action::DeleteCharacter => "Delete the last digit",
+            Action::ToggleExport => "Show or hide the export popup",
+            Action::ToggleChecksums => "Show or hide the checksums popup",
+            Action::ToggleLogs => "Show or hide the log viewer",
+            Action::CommandPalette => "Search and run any command",
+            Action::ReloadConfig => "Reload the configuration files",
+            Action::SetKind(kind) => return format!("Read numbers as {kind}"),
+            Action::SetEndianness(endianness) => {
+                return format!("Read numbers as {endianness} endian");
+            }
+            Action::SetEncoding(encoding) => return format!("Read text as {encoding}"),
+            Action::AddBookmark => "Bookmark the current offset",
+            Action::GoTo(offset) => return format!("Go to offset {offset:#x}"),
+            Action::SetTheme(name) => return format!("Use the {name} theme"),
+            Action::SaveWorkspace(name) => return format!("Save the workspace as {name}"),
+            Action::LoadWorkspace(name) => return format!("Load the workspace {name}"),
+            Action::CompareWith(path) => {
+                return format!("Compare the buffer with {}", path.display());
+            }
+            Action::Tick
+            | Action::Render
+            | Action::Resize(..)
+            | Action::Resume
+            | Action::Error(_)
+            | Action::Info(_)
+            | Action::SetBuffer(_)
+            | Action::SetCompareBuffer(..)
+            | Action::Bookmarked(_)
+            | Action::SetBookmarks(_)
+            | Action::SetCursor(_)
+            | Action::SetSelection(_)
+            | Action::PendingKeys(_)
+            | Action::OfferRestore(_)
+            | Action::RestoreSession(_)
+            | Action::RestoreSelections(_) => return self.to_string(),
+        };
+        description.to_string()
+    }
 }
@@ -1,16 +1,57 @@
-use color_eyre::Result;
-use crossterm::event::KeyEvent;
-use ratatui::prelude::Rect;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::stdout,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{Result, eyre::WrapErr};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use notify::RecommendedWatcher;
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    buffer::Buffer,
+    layout::{Constraint, Layout, Position},
+    prelude::Rect,
+    style::Style,
+    widgets::Tabs,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::{Display, EnumIter, IntoEnumIterator};
 use tokio::sync::mpsc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
-    action::Action,
+    action::{Action, Bookmark},
     component::Component,
-    components::home::Home,
-    config::Config,
-    tui::{Event, Tui},
+    components::{
+        bits::Bits,
+        bitwise::Bitwise,
+        checksums::Checksums,
+        compare::Compare,
+        export::Export,
+        help::Help,
+        hex_view::HexView,
+        home::{Home, parse_hex},
+        inspect::Inspect,
+        log_viewer::LogViewer,
+        palette::CommandPalette,
+        restore_prompt::RestorePrompt,
+        status_bar::StatusBar,
+        tab_at,
+        which_key::WhichKey,
+    },
+    config::{self, Config, get_config_dir},
+    import,
+    keymap::{ChordMatcher, KeyTrie, keymaps},
+    layout::{LayoutNode, Panel},
+    recording::{self, Recorder, ReplayEvents, Start},
+    recovery::{Recovery, SessionState},
+    theme::{Role, Theme, ThemeConfig},
+    tui::{Event, TerminalMode, Tui},
+    workspace::{self, Workspace},
 };
 
 pub struct App {
@@ -18,65 +59,255 @@ pub struct App {
     tick_rate: f64,
     frame_rate: f64,
     components: Vec<Box<dyn Component>>,
+    /// Drawn below the layout of every mode, outside of the focus order.
+    status_bar: StatusBar,
+    /// Reports changes of the configuration files while it is alive.
+    config_watcher: Option<RecommendedWatcher>,
     should_quit: bool,
     should_suspend: bool,
     mode: Mode,
-    last_tick_key_events: Vec<KeyEvent>,
+    /// The focused panel of every mode that has been focused explicitly.
+    focus: HashMap<Mode, Panel>,
+    mouse: bool,
+    /// Where the mode tabs and the panels were drawn last, to find what the mouse points at.
+    tabs_area: Rect,
+    panel_areas: Vec<(Panel, Rect)>,
+    /// Bindings of every mode, rebuilt when the configuration changes.
+    keymaps: HashMap<Mode, KeyTrie>,
+    chords: ChordMatcher,
+    /// The buffer, its interpretation and the file it was read from.
+    session: SessionState,
+    /// The offset the hex view points at.
+    cursor: usize,
+    bookmarks: Vec<Bookmark>,
+    /// Saves the session to be restored after a crash, only while running in the terminal.
+    recovery: Option<Recovery>,
+    /// The files given on the command line, written at the top of a recording.
+    start: Start,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
 }
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Default,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
 pub enum Mode {
+    /// Entering the buffer.
     #[default]
     Home,
+    /// Numeric and text interpretations of the buffer.
+    Inspect,
+    /// Hex dump of the buffer.
+    HexView,
+    /// Bitwise operations between the buffer and a second operand.
+    Bitwise,
+    /// Differences between the buffer and a second one.
+    Compare,
 }
 
 impl App {
-    pub fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
+    pub fn new(tick_rate: f64, frame_rate: f64, mouse: bool) -> Result<Self> {
+        Ok(Self::with_config(
+            Config::new()?,
+            tick_rate,
+            frame_rate,
+            mouse,
+        ))
+    }
+
+    pub fn with_config(config: Config, tick_rate: f64, frame_rate: f64, mouse: bool) -> Self {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
-        Ok(Self {
+        Self {
             tick_rate,
             frame_rate,
             components: vec![
-                Box::new(Home::new()), // , Box::new(FpsCounter::default())
+                Box::new(Home::new()),
+                Box::new(Inspect::new()),
+                Box::new(Bits::new()),
+                Box::new(HexView::new()),
+                Box::new(Export::new()),
+                Box::new(Checksums::new()),
+                Box::new(LogViewer::new()),
+                Box::new(Help::new()),
+                Box::new(CommandPalette::new()),
+                Box::new(WhichKey::new()),
+                Box::new(Bitwise::new()),
+                Box::new(Compare::new()),
+                Box::new(RestorePrompt::new()),
             ],
+            status_bar: StatusBar::new(),
+            config_watcher: None,
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
             mode: Mode::Home,
-            last_tick_key_events: Vec::new(),
+            focus: HashMap::new(),
+            mouse: mouse || config.mouse,
+            tabs_area: Rect::default(),
+            panel_areas: Vec::new(),
+            keymaps: keymaps(&config),
+            chords: ChordMatcher::new(Duration::from_millis(config.chord_timeout)),
+            session: SessionState::default(),
+            cursor: 0,
+            bookmarks: Vec::new(),
+            recovery: None,
+            start: Start::default(),
+            config,
             action_tx,
             action_rx,
-        })
+        }
     }
 
-    pub async fn run(&mut self) -> Result<()> {
-        let mut tui = Tui::new()?
-            // .mouse(true) // uncomment this line to enable mouse support
-            .tick_rate(self.tick_rate)
-            .frame_rate(self.frame_rate);
+    /// Start with the bytes of the file at `path` as the buffer.
+    pub fn open(&mut self, path: &Path) -> Result<()> {
+        let bytes = fs::read(path).wrap_err_with(|| format!("Cannot open {}", path.display()))?;
+        let message = format!("Opened {}, {} bytes", path.display(), bytes.len());
+        self.session.set_file(Some(path.to_path_buf()), &bytes);
+        self.start.file = Some(path.to_path_buf());
+        self.start.input = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        self.action_tx.send(Action::SetBuffer(bytes))?;
+        self.action_tx.send(Action::Info(message))?;
+        Ok(())
+    }
+
+    /// The state a crash would leave to restore.
+    #[cfg(test)]
+    pub fn session(&self) -> &SessionState {
+        &self.session
+    }
+
+    /// Start comparing the buffer with the bytes of the file at `path`, in the Compare mode.
+    pub fn compare_with(&mut self, path: &Path) -> Result<()> {
+        let bytes = self.read_compare(path)?;
+        self.start.compare = Some(path.to_path_buf());
+        self.start.compare_input = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        Ok(())
+    }
+
+    /// Compare the buffer with the bytes of the file at `path`, in the Compare mode. Returns the
+    /// bytes.
+    fn read_compare(&mut self, path: &Path) -> Result<Vec<u8>> {
+        let bytes = fs::read(path).wrap_err_with(|| format!("Cannot open {}", path.display()))?;
+        let message = format!("Comparing with {}, {} bytes", path.display(), bytes.len());
+        self.action_tx.send(Action::SetCompareBuffer(
+            bytes.clone(),
+            Some(path.to_path_buf()),
+        ))?;
+        self.action_tx.send(Action::SwitchMode(Mode::Compare))?;
+        self.action_tx.send(Action::Info(message))?;
+        Ok(bytes)
+    }
+
+    /// Start like the recorded session did, whatever files are given now.
+    pub fn replay_start(&mut self, start: &Start) -> Result<()> {
+        for action in start.actions() {
+            self.action_tx.send(action)?;
+        }
+        self.start.clone_from(start);
+        Ok(())
+    }
+
+    /// Run in the terminal, reloading the configuration when its files change. The events are
+    /// written to `record` and read from `replay` as well as the terminal, if given.
+    ///
+    /// The session is saved to the data directory while it runs and offered to be restored on
+    /// the next start if it does not end normally.
+    pub async fn run(&mut self, record: Option<&Path>, replay: Option<&Path>) -> Result<()> {
+        let (mut recovery, interrupted) = Recovery::start(&self.config.config.data_dir);
+        match (interrupted, &self.session.file) {
+            // Restoring would replace the file opened now.
+            (Some(state), Some(file)) => {
+                let message = format!(
+                    "The last session did not end normally, not restoring it over {}",
+                    file.display()
+                );
+                info!("{message}: {state:?}");
+                self.action_tx.send(Action::Info(message))?;
+            }
+            (Some(state), None) => {
+                info!("Offering to restore {state:?}");
+                self.action_tx.send(Action::OfferRestore(state))?;
+            }
+            (None, _) => {}
+        }
+        if self.session.file.is_some() {
+            recovery.update(&self.session);
+        }
+        self.recovery = Some(recovery);
+        let watcher_tx = self.action_tx.clone();
+        match config::watch(&get_config_dir(), move || {
+            let _ = watcher_tx.send(Action::ReloadConfig);
+        }) {
+            Ok(watcher) => self.config_watcher = Some(watcher),
+            Err(err) => {
+                let message = format!("Not watching the configuration for changes: {err}");
+                warn!("{message}");
+                self.action_tx.send(Action::Error(message))?;
+            }
+        }
+        let mut tui = match replay {
+            Some(path) => {
+                let recording = recording::read(path)?;
+                self.replay_start(&recording.start)?;
+                let events = ReplayEvents::new(recording.events);
+                Tui::with_backend(CrosstermBackend::new(stdout()), events)?
+            }
+            None => Tui::new()?,
+        };
+        if let Some(path) = record {
+            tui = tui.recorder(Recorder::create(path, &self.start)?);
+        }
+        let result = self.run_with(&mut tui).await;
+        if let Some(mut recovery) = self.recovery.take() {
+            match result {
+                Ok(()) => recovery.finish(),
+                Err(_) => recovery.flush(),
+            }
+        }
+        result
+    }
+
+    /// Run on `tui` until the app quits, e.g. on a [`ratatui::backend::TestBackend`] with
+    /// [`crate::tui::ScriptedEvents`] that end in [`Event::Quit`].
+    pub async fn run_with<B: Backend + TerminalMode>(&mut self, tui: &mut Tui<B>) -> Result<()> {
+        tui.mouse = self.mouse;
+        tui.paste = true;
+        tui.tick_rate = self.tick_rate;
+        tui.frame_rate = self.frame_rate;
         tui.enter()?;
 
         for component in self.components.iter_mut() {
             component.register_action_handler(self.action_tx.clone())?;
         }
-        for component in self.components.iter_mut() {
-            component.register_config_handler(self.config.clone())?;
-        }
+        self.status_bar
+            .register_action_handler(self.action_tx.clone())?;
+        self.register_config()?;
         for component in self.components.iter_mut() {
             component.init(tui.size()?)?;
         }
 
         let action_tx = self.action_tx.clone();
+        if let Some(summary) = config::summarize(&self.config.diagnostics) {
+            action_tx.send(Action::Error(summary))?;
+        }
         loop {
-            self.handle_events(&mut tui).await?;
-            self.handle_actions(&mut tui)?;
+            self.handle_events(tui).await?;
+            self.handle_actions(tui)?;
             if self.should_suspend {
                 tui.suspend()?;
                 action_tx.send(Action::Resume)?;
                 action_tx.send(Action::ClearScreen)?;
-                // tui.mouse(true);
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
@@ -87,7 +318,7 @@ impl App {
         Ok(())
     }
 
-    async fn handle_events(&mut self, tui: &mut Tui) -> Result<()> {
+    async fn handle_events<B: Backend + TerminalMode>(&mut self, tui: &mut Tui<B>) -> Result<()> {
         let Some(event) = tui.next_event().await else {
             return Ok(());
         };
@@ -97,10 +328,29 @@ impl App {
             Event::Tick => action_tx.send(Action::Tick)?,
             Event::Render => action_tx.send(Action::Render)?,
             Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
-            Event::Key(key) => self.handle_key_event(key)?,
+            Event::Key(key) if !self.target_captures_keys() => self.handle_key_event(key)?,
+            Event::Mouse(mouse) => self.handle_mouse_event(mouse)?,
+            Event::Paste(ref text) if self.target_captures_keys() => self.paste_as_keys(text)?,
+            Event::Paste(ref text) => match import::parse(text) {
+                Ok(bytes) => action_tx.send(Action::SetBuffer(bytes))?,
+                Err(err) => action_tx.send(Action::Error(format!("Failed to import: {err}")))?,
+            },
             _ => {}
         }
-        for component in self.components.iter_mut() {
+        let is_input = matches!(event, Event::Key(_) | Event::Mouse(_));
+        let target = self.input_target();
+        let panels = self.panels();
+        for (i, component) in self.components.iter_mut().enumerate() {
+            let receives = if is_input {
+                target == Some(i)
+            } else {
+                component
+                    .panel()
+                    .is_none_or(|panel| panels.contains(&panel))
+            };
+            if !receives {
+                continue;
+            }
             if let Some(action) = component.handle_events(Some(event.clone()))? {
                 action_tx.send(action)?;
             }
@@ -108,49 +358,378 @@ impl App {
         Ok(())
     }
 
+    /// Panels of the active mode's layout, in focus order.
+    fn panels(&self) -> Vec<Panel> {
+        self.config
+            .layouts
+            .get(&self.mode)
+            .map(LayoutNode::panels)
+            .unwrap_or_default()
+    }
+
+    fn focused_panel(&self) -> Option<Panel> {
+        let panels = self.panels();
+        self.focus
+            .get(&self.mode)
+            .filter(|panel| panels.contains(panel))
+            .or(panels.first())
+            .copied()
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        let panels = self.panels();
+        let Some(current) = self
+            .focused_panel()
+            .and_then(|focused| panels.iter().position(|panel| *panel == focused))
+        else {
+            return;
+        };
+        let next = if forward {
+            (current + 1) % panels.len()
+        } else {
+            (current + panels.len() - 1) % panels.len()
+        };
+        self.focus.insert(self.mode, panels[next]);
+    }
+
+    /// Index of the component that receives key events and targeted actions: the topmost
+    /// component capturing focus, otherwise the component of the focused panel.
+    fn input_target(&self) -> Option<usize> {
+        if let Some(i) = self.components.iter().rposition(|c| c.captures_focus()) {
+            return Some(i);
+        }
+        let focused = self.focused_panel()?;
+        self.components
+            .iter()
+            .position(|c| c.panel() == Some(focused))
+    }
+
+    fn target_captures_keys(&self) -> bool {
+        self.input_target()
+            .is_some_and(|i| self.components[i].captures_keys())
+    }
+
+    /// Type pasted text into the component capturing keys, e.g. a name into the palette, instead
+    /// of importing it. Line breaks are dropped.
+    fn paste_as_keys(&mut self, text: &str) -> Result<()> {
+        let Some(target) = self.input_target() else {
+            return Ok(());
+        };
+        for c in text.chars().filter(|c| !c.is_control()) {
+            let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            if let Some(action) = self.components[target].handle_key_event(key)? {
+                self.action_tx.send(action)?;
+            }
+        }
+        Ok(())
+    }
+
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
-        let action_tx = self.action_tx.clone();
-        let Some(keymap) = self.config.keybindings.get(&self.mode) else {
+        let Some(keymap) = self.keymaps.get(&self.mode) else {
+            return Ok(());
+        };
+        let was_pending = !self.chords.pending().is_empty();
+        for action in self.chords.press(keymap, key, Instant::now()) {
+            info!("Got action: {action:?}");
+            self.action_tx.send(action)?;
+        }
+        if was_pending || !self.chords.pending().is_empty() {
+            let pending = self.chords.pending().to_vec();
+            self.action_tx.send(Action::PendingKeys(pending))?;
+        }
+        Ok(())
+    }
+
+    /// Run or drop the pending chord once its timeout passed.
+    fn expire_chord(&mut self) -> Result<()> {
+        if self.chords.pending().is_empty() {
+            return Ok(());
+        }
+        let Some(keymap) = self.keymaps.get(&self.mode) else {
             return Ok(());
         };
-        match keymap.get(&vec![key]) {
-            Some(action) => {
-                info!("Got action: {action:?}");
-                action_tx.send(action.clone())?;
-            }
-            _ => {
-                // If the key was not handled as a single key action,
-                // then consider it for multi-key combinations.
-                self.last_tick_key_events.push(key);
-
-                // Check for multi-key combinations
-                if let Some(action) = keymap.get(&self.last_tick_key_events) {
-                    info!("Got action: {action:?}");
-                    action_tx.send(action.clone())?;
+        if let Some(action) = self.chords.expire(keymap, Instant::now()) {
+            info!("Got action: {action:?}");
+            self.action_tx.send(action)?;
+        }
+        if self.chords.pending().is_empty() {
+            self.action_tx.send(Action::PendingKeys(Vec::new()))?;
+        }
+        Ok(())
+    }
+
+    fn cancel_chord(&mut self) -> Result<()> {
+        if !self.chords.pending().is_empty() {
+            self.chords.reset();
+            self.action_tx.send(Action::PendingKeys(Vec::new()))?;
+        }
+        Ok(())
+    }
+
+    /// Switch modes by clicking their tabs, focus panels by clicking them and scroll with the
+    /// wheel. The event itself is handled by the input target afterwards.
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<()> {
+        let position = Position::new(mouse.column, mouse.row);
+        let overlay_open = self.components.iter().any(|c| c.captures_focus());
+        let under_pointer = self
+            .panel_areas
+            .iter()
+            .find(|(_, area)| area.contains(position))
+            .map(|(panel, _)| *panel);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if self.tabs_area.contains(position) => {
+                let modes: Vec<String> = Mode::iter().map(|mode| mode.to_string()).collect();
+                if let Some(mode) = tab_at(&modes, mouse.column - self.tabs_area.x)
+                    .and_then(|i| Mode::iter().nth(i))
+                {
+                    self.action_tx.send(Action::SwitchMode(mode))?;
+                }
+            }
+            MouseEventKind::Down(_) | MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                if !overlay_open =>
+            {
+                if let Some(panel) = under_pointer {
+                    self.focus.insert(self.mode, panel);
+                }
+            }
+            _ => {}
+        }
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.action_tx.send(Action::ScrollUp)?,
+            MouseEventKind::ScrollDown => self.action_tx.send(Action::ScrollDown)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Hand the current configuration to every component.
+    fn register_config(&mut self) -> Result<()> {
+        for component in self.components.iter_mut() {
+            component.register_config_handler(self.config.clone())?;
+        }
+        self.status_bar.register_config_handler(self.config.clone())
+    }
+
+    fn reload_config(&mut self) -> Result<()> {
+        let message = match Config::load(&get_config_dir()) {
+            Ok(config) => {
+                self.config = config;
+                self.keymaps = keymaps(&self.config);
+                self.chords
+                    .set_timeout(Duration::from_millis(self.config.chord_timeout));
+                self.cancel_chord()?;
+                self.register_config()?;
+                match config::summarize(&self.config.diagnostics) {
+                    Some(summary) => Action::Error(summary),
+                    None => Action::Info("Configuration reloaded".to_string()),
+                }
+            }
+            Err(err) => Action::Error(format!("Failed to reload the configuration: {err}")),
+        };
+        self.action_tx.send(message)?;
+        Ok(())
+    }
+
+    /// Switch to the built-in theme `name`, keeping the palette and roles of the configuration.
+    fn set_theme(&mut self, name: &str) -> Result<()> {
+        let config = ThemeConfig {
+            name: name.to_string(),
+            ..self.config.theme.config.clone()
+        };
+        let (theme, problems) = Theme::new(config);
+        if let Some(problem) = problems.first() {
+            self.action_tx.send(Action::Error(problem.clone()))?;
+        }
+        self.config.theme = theme;
+        self.register_config()
+    }
+
+    /// Bring back the buffer, settings and file of an earlier session.
+    fn restore_session(&mut self, state: &SessionState) -> Result<()> {
+        let bytes = match &state.file {
+            Some(file) => match fs::read(file) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    let message = format!("Cannot open {}: {err}", file.display());
+                    self.action_tx.send(Action::Error(message))?;
+                    return Ok(());
+                }
+            },
+            None => parse_hex(&state.input),
+        };
+        self.session.set_file(state.file.clone(), &bytes);
+        if state.file.is_some() {
+            self.action_tx.send(Action::SetBuffer(bytes))?;
+        }
+        for action in state.actions() {
+            self.action_tx.send(action)?;
+        }
+        if let Some(recovery) = &mut self.recovery {
+            recovery.update(&self.session);
+        }
+        self.action_tx
+            .send(Action::Info("Restored the last session".to_string()))?;
+        Ok(())
+    }
+
+    /// Everything needed to pick up the investigation later.
+    fn workspace(&self) -> Workspace {
+        Workspace {
+            version: workspace::VERSION,
+            // Only one of them is set, the buffer once it no longer matches the file.
+            file: self.session.file.clone(),
+            input: self.session.input.clone(),
+            kind: self.session.kind,
+            endianness: self.session.endianness,
+            encoding: self.session.encoding,
+            offset: self.cursor,
+            bookmarks: self.bookmarks.clone(),
+            mode: self.mode,
+            focus: self.focus.iter().map(|(m, p)| (*m, *p)).collect(),
+            layouts: self
+                .config
+                .layouts
+                .iter()
+                .map(|(mode, layout)| (*mode, layout.clone()))
+                .collect(),
+            selections: self
+                .components
+                .iter()
+                .filter_map(|c| Some((c.panel()?, c.selection()?)))
+                .collect(),
+        }
+    }
+
+    fn save_workspace(&mut self, name: &str) -> Result<()> {
+        let path = workspace::path(&self.config.config.data_dir, name);
+        let message = match workspace::save(&path, &self.workspace()) {
+            Ok(()) => Action::Info(format!("Saved the workspace to {}", path.display())),
+            Err(err) => Action::Error(format!("{err:#}")),
+        };
+        self.action_tx.send(message)?;
+        Ok(())
+    }
+
+    fn load_workspace(&mut self, name: &str) -> Result<()> {
+        let path = workspace::path(&self.config.config.data_dir, name);
+        let loaded = workspace::load(&path).and_then(|workspace| {
+            let bytes = match &workspace.file {
+                Some(file) => {
+                    fs::read(file).wrap_err_with(|| format!("Cannot open {}", file.display()))?
                 }
+                None => parse_hex(&workspace.input),
+            };
+            Ok((workspace, bytes))
+        });
+        let (workspace, bytes) = match loaded {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.action_tx.send(Action::Error(format!("{err:#}")))?;
+                return Ok(());
+            }
+        };
+        info!("Loading the workspace {}", path.display());
+        for (mode, layout) in &workspace.layouts {
+            self.config.layouts.insert(*mode, layout.clone());
+        }
+        self.register_config()?;
+        self.focus = workspace.focus.iter().map(|(m, p)| (*m, *p)).collect();
+        self.session.set_file(workspace.file.clone(), &bytes);
+        let message = if workspace.is_newer() {
+            format!(
+                "Loaded {}, written by a newer version, parts may be missing",
+                path.display()
+            )
+        } else {
+            format!("Loaded {}", path.display())
+        };
+        for action in [
+            Action::SetBuffer(bytes),
+            Action::SetKind(workspace.kind),
+            Action::SetEndianness(workspace.endianness),
+            Action::SetEncoding(workspace.encoding),
+            Action::SetBookmarks(workspace.bookmarks),
+            Action::SwitchMode(workspace.mode),
+            Action::GoTo(workspace.offset),
+            // After the buffer is set, which clears the selections.
+            Action::RestoreSelections(workspace.selections),
+        ] {
+            self.action_tx.send(action)?;
+        }
+        self.action_tx.send(Action::Info(message))?;
+        Ok(())
+    }
+
+    fn restore_selections(&mut self, selections: &BTreeMap<Panel, Value>) -> Result<()> {
+        for component in self.components.iter_mut() {
+            let Some(selection) = component.panel().and_then(|panel| selections.get(&panel)) else {
+                continue;
+            };
+            if let Err(err) = component.select(selection.clone()) {
+                let message = format!("Failed to restore a selection: {err}");
+                warn!("{message}");
+                self.action_tx.send(Action::Error(message))?;
             }
         }
         Ok(())
     }
 
-    fn handle_actions(&mut self, tui: &mut Tui) -> Result<()> {
+    fn handle_actions<B: Backend + TerminalMode>(&mut self, tui: &mut Tui<B>) -> Result<()> {
         while let Ok(action) = self.action_rx.try_recv() {
             if action != Action::Tick && action != Action::Render {
                 debug!("{action:?}");
             }
             match action {
                 Action::Tick => {
-                    self.last_tick_key_events.drain(..);
+                    self.expire_chord()?;
+                    if let Some(recovery) = &mut self.recovery {
+                        recovery.tick(Instant::now());
+                    }
                 }
                 Action::Quit => self.should_quit = true,
+                Action::SwitchMode(mode) => {
+                    self.mode = mode;
+                    self.cancel_chord()?;
+                }
+                Action::FocusNext => self.move_focus(true),
+                Action::FocusPrevious => self.move_focus(false),
                 Action::Suspend => self.should_suspend = true,
                 Action::Resume => self.should_suspend = false,
                 Action::ClearScreen => tui.terminal.clear()?,
                 Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
                 Action::Render => self.render(tui)?,
+                Action::ReloadConfig => self.reload_config()?,
+                Action::SetTheme(ref name) => self.set_theme(name)?,
+                Action::RestoreSession(ref state) => self.restore_session(state)?,
+                Action::SetCursor(offset) => self.cursor = offset,
+                Action::Bookmarked(ref bookmark) => {
+                    self.bookmarks.retain(|b| b.offset != bookmark.offset);
+                    self.bookmarks.push(bookmark.clone());
+                }
+                Action::SetBookmarks(ref bookmarks) => self.bookmarks.clone_from(bookmarks),
+                Action::CompareWith(ref path) => {
+                    if let Err(err) = self.read_compare(path) {
+                        self.action_tx.send(Action::Error(format!("{err:#}")))?;
+                    }
+                }
+                Action::SaveWorkspace(ref name) => self.save_workspace(name)?,
+                Action::LoadWorkspace(ref name) => self.load_workspace(name)?,
+                Action::RestoreSelections(ref selections) => self.restore_selections(selections)?,
                 _ => {}
             }
-            for component in self.components.iter_mut() {
+            if self.session.update(&action)
+                && let Some(recovery) = &mut self.recovery
+            {
+                recovery.update(&self.session);
+            }
+            if let Some(action) = self.status_bar.update(action.clone())? {
+                self.action_tx.send(action)?
+            };
+            let target = self.input_target();
+            for (i, component) in self.components.iter_mut().enumerate() {
+                if action.is_targeted() && target != Some(i) {
+                    continue;
+                }
                 if let Some(action) = component.update(action.clone())? {
                     self.action_tx.send(action)?
                 };
@@ -159,22 +738,96 @@ impl App {
         Ok(())
     }
 
-    fn handle_resize(&mut self, tui: &mut Tui, w: u16, h: u16) -> Result<()> {
+    fn handle_resize<B: Backend + TerminalMode>(
+        &mut self,
+        tui: &mut Tui<B>,
+        w: u16,
+        h: u16,
+    ) -> Result<()> {
         tui.resize(Rect::new(0, 0, w, h))?;
         self.render(tui)?;
         Ok(())
     }
 
-    fn render(&mut self, tui: &mut Tui) -> Result<()> {
+    fn render<B: Backend + TerminalMode>(&mut self, tui: &mut Tui<B>) -> Result<()> {
+        let focus_style = self
+            .config
+            .styles
+            .get(&self.mode)
+            .and_then(|styles| styles.get("focus"))
+            .copied()
+            .unwrap_or(self.config.theme.style(Role::Focus));
+        let header_active = self.config.theme.style(Role::HeaderActive);
+        let focused = self.focused_panel();
+        let overlay_open = self.components.iter().any(|c| c.captures_focus());
         tui.draw(|frame| {
-            for component in self.components.iter_mut() {
-                if let Err(err) = component.draw(frame, frame.area()) {
-                    let _ = self
-                        .action_tx
-                        .send(Action::Error(format!("Failed to draw: {:?}", err)));
+            let [tabs, body, status] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .areas(frame.area());
+            self.tabs_area = tabs;
+            let selected = Mode::iter().position(|mode| mode == self.mode);
+            frame.render_widget(
+                Tabs::new(Mode::iter().map(|mode| mode.to_string()))
+                    .select(selected)
+                    .highlight_style(header_active),
+                tabs,
+            );
+
+            let components = &mut self.components;
+            let requested = |panel| {
+                components
+                    .iter()
+                    .find(|c| c.panel() == Some(panel))
+                    .map_or(Constraint::Fill(1), |c| c.constraint())
+            };
+            let areas = self
+                .config
+                .layouts
+                .get(&self.mode)
+                .map(|layout| layout.areas(body, &requested))
+                .unwrap_or_default();
+            self.panel_areas.clone_from(&areas);
+
+            let mut results = Vec::new();
+            for (panel, area) in areas {
+                let Some(component) = components.iter_mut().find(|c| c.panel() == Some(panel))
+                else {
+                    continue;
+                };
+                results.push(component.draw(frame, area));
+                if !overlay_open && focused == Some(panel) {
+                    highlight_border(frame.buffer_mut(), area, focus_style);
                 }
             }
+            for component in components.iter_mut().filter(|c| c.panel().is_none()) {
+                results.push(component.draw(frame, body));
+            }
+            results.push(self.status_bar.draw(frame, status));
+            for err in results.into_iter().filter_map(Result::err) {
+                let _ = self
+                    .action_tx
+                    .send(Action::Error(format!("Failed to draw: {:?}", err)));
+            }
         })?;
         Ok(())
     }
 }
+
+/// Apply `style` to the outermost cells of `area`, keeping the symbols drawn by the component.
+fn highlight_border(buf: &mut Buffer, area: Rect, style: Style) {
+    let area = area.intersection(buf.area);
+    if area.is_empty() {
+        return;
+    }
+    for x in area.left()..area.right() {
+        buf[(x, area.top())].set_style(style);
+        buf[(x, area.bottom() - 1)].set_style(style);
+    }
+    for y in area.top()..area.bottom() {
+        buf[(area.left(), y)].set_style(style);
+        buf[(area.right() - 1, y)].set_style(style);
+    }
+}
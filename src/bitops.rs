@@ -0,0 +1,222 @@
+//! Bitwise operations on byte buffers interpreted as big-endian unsigned integers of arbitrary
+//! width.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+/// Left-pad `bytes` with zeros to `len` bytes.
+fn pad(bytes: &[u8], len: usize) -> Vec<u8> {
+    let mut padded = vec![0; len.saturating_sub(bytes.len())];
+    padded.extend_from_slice(bytes);
+    padded
+}
+
+fn zip_with(a: &[u8], b: &[u8], f: impl Fn(u8, u8) -> u8) -> Vec<u8> {
+    let len = a.len().max(b.len());
+    pad(a, len)
+        .into_iter()
+        .zip(pad(b, len))
+        .map(|(a, b)| f(a, b))
+        .collect()
+}
+
+fn bit(bytes: &[u8], index: usize) -> bool {
+    bytes[index / 8] & (0x80 >> (index % 8)) != 0
+}
+
+/// Build a buffer of `len` bytes where bit `i` (counted from the most significant bit) is
+/// `f(i)`.
+fn from_bits(len: usize, f: impl Fn(usize) -> bool) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    for i in (0..len * 8).filter(|i| f(*i)) {
+        bytes[i / 8] |= 0x80 >> (i % 8);
+    }
+    bytes
+}
+
+pub fn shift_left(bytes: &[u8], amount: usize) -> Vec<u8> {
+    let bits = bytes.len() * 8;
+    from_bits(bytes.len(), |i| {
+        i.checked_add(amount)
+            .is_some_and(|j| j < bits && bit(bytes, j))
+    })
+}
+
+pub fn shift_right(bytes: &[u8], amount: usize) -> Vec<u8> {
+    from_bits(bytes.len(), |i| i >= amount && bit(bytes, i - amount))
+}
+
+pub fn rotate_left(bytes: &[u8], amount: usize) -> Vec<u8> {
+    let bits = bytes.len() * 8;
+    if bits == 0 {
+        return Vec::new();
+    }
+    let amount = amount % bits;
+    from_bits(bytes.len(), |i| bit(bytes, (i + amount) % bits))
+}
+
+pub fn rotate_right(bytes: &[u8], amount: usize) -> Vec<u8> {
+    let bits = bytes.len() * 8;
+    if bits == 0 {
+        return Vec::new();
+    }
+    rotate_left(bytes, bits - amount % bits)
+}
+
+pub fn reverse_bits(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().rev().map(|b| b.reverse_bits()).collect()
+}
+
+pub fn count_ones(bytes: &[u8]) -> usize {
+    bytes.iter().map(|b| b.count_ones() as usize).sum()
+}
+
+pub fn leading_zeros(bytes: &[u8]) -> usize {
+    let bits = bytes.len() * 8;
+    (0..bits).find(|i| bit(bytes, *i)).unwrap_or(bits)
+}
+
+pub fn trailing_zeros(bytes: &[u8]) -> usize {
+    let bits = bytes.len() * 8;
+    (0..bits)
+        .rev()
+        .find(|i| bit(bytes, *i))
+        .map_or(bits, |i| bits - 1 - i)
+}
+
+/// The value of `bytes` saturated to `usize`, used for shift and rotate amounts.
+fn amount(bytes: &[u8]) -> usize {
+    let significant = &bytes[bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len())..];
+    if significant.len() > size_of::<usize>() {
+        return usize::MAX;
+    }
+    significant
+        .iter()
+        .fold(0, |acc, b| (acc << 8) | usize::from(*b))
+}
+
+/// `value` as a big-endian buffer of `len` bytes, truncated if it does not fit.
+fn from_count(value: usize, len: usize) -> Vec<u8> {
+    let bytes = (value as u64).to_be_bytes();
+    let padded = pad(&bytes, len);
+    padded[padded.len() - len..].to_vec()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum Operation {
+    #[strum(to_string = "A AND B")]
+    And,
+    #[strum(to_string = "A OR B")]
+    Or,
+    #[strum(to_string = "A XOR B")]
+    Xor,
+    #[strum(to_string = "A NAND B")]
+    Nand,
+    #[strum(to_string = "A << B")]
+    ShiftLeft,
+    #[strum(to_string = "A >> B")]
+    ShiftRight,
+    #[strum(to_string = "A rotl B")]
+    RotateLeft,
+    #[strum(to_string = "A rotr B")]
+    RotateRight,
+    #[strum(to_string = "NOT A")]
+    Not,
+    #[strum(to_string = "reverse A")]
+    Reverse,
+    #[strum(to_string = "popcount A")]
+    CountOnes,
+    #[strum(to_string = "clz A")]
+    LeadingZeros,
+    #[strum(to_string = "ctz A")]
+    TrailingZeros,
+}
+
+impl Operation {
+    /// Apply the operation. Binary operations work on the width of the wider operand, shifts and
+    /// unary operations on the width of `a`. Counts are returned with the width of `a`.
+    pub fn apply(&self, a: &[u8], b: &[u8]) -> Vec<u8> {
+        match self {
+            Operation::And => zip_with(a, b, |a, b| a & b),
+            Operation::Or => zip_with(a, b, |a, b| a | b),
+            Operation::Xor => zip_with(a, b, |a, b| a ^ b),
+            Operation::Nand => zip_with(a, b, |a, b| !(a & b)),
+            Operation::ShiftLeft => shift_left(a, amount(b)),
+            Operation::ShiftRight => shift_right(a, amount(b)),
+            Operation::RotateLeft => rotate_left(a, amount(b)),
+            Operation::RotateRight => rotate_right(a, amount(b)),
+            Operation::Not => a.iter().map(|b| !b).collect(),
+            Operation::Reverse => reverse_bits(a),
+            Operation::CountOnes => from_count(count_ones(a), a.len()),
+            Operation::LeadingZeros => from_count(leading_zeros(a), a.len()),
+            Operation::TrailingZeros => from_count(trailing_zeros(a), a.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_binary_operations_pad_shorter_operand() {
+        let a = [0x12, 0x34];
+        let b = [0xff];
+        assert_eq!(Operation::And.apply(&a, &b), vec![0x00, 0x34]);
+        assert_eq!(Operation::Or.apply(&a, &b), vec![0x12, 0xff]);
+        assert_eq!(Operation::Xor.apply(&a, &b), vec![0x12, 0xcb]);
+        assert_eq!(Operation::Nand.apply(&a, &b), vec![0xff, 0xcb]);
+    }
+
+    #[test]
+    fn test_shifts_and_rotates_match_integers() {
+        let value: u16 = 0xb00f;
+        let a = value.to_be_bytes();
+        for amount in 0..20u32 {
+            let b = [amount as u8];
+            let expected = |v: u16| v.to_be_bytes().to_vec();
+            assert_eq!(
+                Operation::ShiftLeft.apply(&a, &b),
+                expected(value.checked_shl(amount).unwrap_or(0))
+            );
+            assert_eq!(
+                Operation::ShiftRight.apply(&a, &b),
+                expected(value.checked_shr(amount).unwrap_or(0))
+            );
+            assert_eq!(
+                Operation::RotateLeft.apply(&a, &b),
+                expected(value.rotate_left(amount))
+            );
+            assert_eq!(
+                Operation::RotateRight.apply(&a, &b),
+                expected(value.rotate_right(amount))
+            );
+        }
+    }
+
+    #[test]
+    fn test_unary_operations_match_integers() {
+        let value: u32 = 0x0012_3400;
+        let a = value.to_be_bytes();
+        assert_eq!(
+            Operation::Reverse.apply(&a, &[]),
+            value.reverse_bits().to_be_bytes()
+        );
+        assert_eq!(Operation::Not.apply(&a, &[]), (!value).to_be_bytes());
+        assert_eq!(count_ones(&a), value.count_ones() as usize);
+        assert_eq!(leading_zeros(&a), value.leading_zeros() as usize);
+        assert_eq!(trailing_zeros(&a), value.trailing_zeros() as usize);
+        assert_eq!(Operation::CountOnes.apply(&a, &[]), vec![0, 0, 0, 5]);
+    }
+
+    #[test]
+    fn test_empty_and_zero_operands() {
+        assert_eq!(leading_zeros(&[0, 0]), 16);
+        assert_eq!(trailing_zeros(&[0, 0]), 16);
+        assert_eq!(Operation::RotateRight.apply(&[], &[1]), Vec::<u8>::new());
+        assert_eq!(Operation::ShiftLeft.apply(&[1], &[0xff; 16]), vec![0]);
+        assert_eq!(Operation::RotateLeft.apply(&[1], &[0xff; 16]), vec![0x80]);
+    }
+}
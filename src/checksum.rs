@@ -0,0 +1,334 @@
+use lazy_static::lazy_static;
+use md5::Md5;
+use serde::{Deserialize, Deserializer, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Parameters of a CRC in the Rocksoft/Williams model, as used by the CRC catalogue.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CrcParams {
+    pub name: String,
+    pub width: u8,
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub poly: u64,
+    #[serde(default, deserialize_with = "deserialize_u64")]
+    pub init: u64,
+    #[serde(default)]
+    pub refin: bool,
+    #[serde(default)]
+    pub refout: bool,
+    #[serde(default, deserialize_with = "deserialize_u64")]
+    pub xorout: u64,
+}
+
+impl CrcParams {
+    fn new(name: &str, width: u8, poly: u64, init: u64, reflected: bool, xorout: u64) -> CrcParams {
+        CrcParams {
+            name: name.to_string(),
+            width,
+            poly,
+            init,
+            refin: reflected,
+            refout: reflected,
+            xorout,
+        }
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - u32::from(self.width))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=64).contains(&self.width) {
+            return Err(format!("{}: width must be between 1 and 64", self.name));
+        }
+        for (field, value) in [
+            ("poly", self.poly),
+            ("init", self.init),
+            ("xorout", self.xorout),
+        ] {
+            if value & !self.mask() != 0 {
+                return Err(format!(
+                    "{}: {field} {value:#x} exceeds {} bits",
+                    self.name, self.width
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute the CRC of `bytes`, bit by bit so that any width up to 64 bits is supported.
+    pub fn checksum(&self, bytes: &[u8]) -> u64 {
+        let mask = self.mask();
+        let top = 1u64 << (self.width - 1);
+        let mut crc = self.init & mask;
+        for &byte in bytes {
+            let byte = if self.refin {
+                byte.reverse_bits()
+            } else {
+                byte
+            };
+            for i in (0..8).rev() {
+                let bit = (byte >> i) & 1 == 1;
+                let msb = crc & top != 0;
+                crc = (crc << 1) & mask;
+                if bit != msb {
+                    crc ^= self.poly;
+                }
+            }
+        }
+        if self.refout {
+            crc = crc.reverse_bits() >> (64 - u32::from(self.width));
+        }
+        (crc ^ self.xorout) & mask
+    }
+
+    /// The checksum formatted as zero-padded hex of the CRC's width.
+    pub fn format(&self, bytes: &[u8]) -> String {
+        let digits = usize::from(self.width).div_ceil(4);
+        format!("{:0digits$x}", self.checksum(bytes))
+    }
+}
+
+/// Accept integers as well as strings such as `"0x04c11db7"`, the configuration backends only
+/// store signed 64 bit integers.
+fn deserialize_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Int(u64),
+        Str(String),
+    }
+    match Value::deserialize(deserializer)? {
+        Value::Int(value) => Ok(value),
+        Value::Str(s) => {
+            let s = s.trim();
+            let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => s.parse(),
+            };
+            parsed.map_err(|_| serde::de::Error::custom(format!("invalid integer `{s}`")))
+        }
+    }
+}
+
+lazy_static! {
+    /// A selection of the most common algorithms from the CRC catalogue.
+    pub static ref CRC_CATALOG: Vec<CrcParams> = vec![
+        CrcParams::new("CRC-8/SMBUS", 8, 0x07, 0x00, false, 0x00),
+        CrcParams::new("CRC-8/MAXIM-DOW", 8, 0x31, 0x00, true, 0x00),
+        CrcParams::new("CRC-8/AUTOSAR", 8, 0x2f, 0xff, false, 0xff),
+        CrcParams::new("CRC-16/ARC", 16, 0x8005, 0x0000, true, 0x0000),
+        CrcParams::new("CRC-16/IBM-3740", 16, 0x1021, 0xffff, false, 0x0000),
+        CrcParams::new("CRC-16/XMODEM", 16, 0x1021, 0x0000, false, 0x0000),
+        CrcParams::new("CRC-16/KERMIT", 16, 0x1021, 0x0000, true, 0x0000),
+        CrcParams::new("CRC-16/MODBUS", 16, 0x8005, 0xffff, true, 0x0000),
+        CrcParams::new("CRC-32/ISO-HDLC", 32, 0x04c11db7, 0xffffffff, true, 0xffffffff),
+        CrcParams::new("CRC-32/ISCSI", 32, 0x1edc6f41, 0xffffffff, true, 0xffffffff),
+        CrcParams::new("CRC-32/BZIP2", 32, 0x04c11db7, 0xffffffff, false, 0xffffffff),
+        CrcParams::new("CRC-32/MPEG-2", 32, 0x04c11db7, 0xffffffff, false, 0x00000000),
+        CrcParams::new("CRC-64/ECMA-182", 64, 0x42f0e1eba9ea3693, 0, false, 0),
+        CrcParams::new("CRC-64/XZ", 64, 0x42f0e1eba9ea3693, u64::MAX, true, u64::MAX),
+        CrcParams::new("CRC-64/GO-ISO", 64, 0x1b, u64::MAX, true, u64::MAX),
+    ];
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChecksumConfig {
+    /// Names of the CRC algorithms shown, one row each.
+    #[serde(default = "ChecksumConfig::default_crc")]
+    pub crc: Vec<String>,
+    /// User defined algorithms, selectable like the ones from [`CRC_CATALOG`].
+    #[serde(default)]
+    pub custom_crc: Vec<CrcParams>,
+}
+
+impl ChecksumConfig {
+    fn default_crc() -> Vec<String> {
+        ["CRC-8/SMBUS", "CRC-16/ARC", "CRC-32/ISO-HDLC", "CRC-64/XZ"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    /// The catalogue followed by every valid custom algorithm, invalid ones are reported when the
+    /// configuration is loaded.
+    pub fn algorithms(&self) -> Vec<CrcParams> {
+        CRC_CATALOG
+            .iter()
+            .chain(self.custom_crc.iter().filter(|crc| crc.validate().is_ok()))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for ChecksumConfig {
+    fn default() -> Self {
+        Self {
+            crc: Self::default_crc(),
+            custom_crc: Vec::new(),
+        }
+    }
+}
+
+pub fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + u32::from(byte)) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
+
+pub fn fletcher16(bytes: &[u8]) -> u16 {
+    let (mut a, mut b) = (0u16, 0u16);
+    for &byte in bytes {
+        a = (a + u16::from(byte)) % 255;
+        b = (b + a) % 255;
+    }
+    (b << 8) | a
+}
+
+/// Fletcher-32 over little-endian 16 bit words, an odd trailing byte is zero-padded.
+pub fn fletcher32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (0u32, 0u32);
+    for word in bytes.chunks(2) {
+        let word = u32::from(word[0]) | word.get(1).map_or(0, |hi| u32::from(*hi) << 8);
+        a = (a + word) % 65535;
+        b = (b + a) % 65535;
+    }
+    (b << 16) | a
+}
+
+pub fn fnv1a32(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x01000193)
+    })
+}
+
+pub fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x00000100000001b3)
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Every checksum and hash that does not need parameters, as `(name, hex digest)` pairs.
+pub fn digests(bytes: &[u8]) -> Vec<(&'static str, String)> {
+    vec![
+        ("Adler-32", format!("{:08x}", adler32(bytes))),
+        ("Fletcher-16", format!("{:04x}", fletcher16(bytes))),
+        ("Fletcher-32", format!("{:08x}", fletcher32(bytes))),
+        ("FNV-1a 32", format!("{:08x}", fnv1a32(bytes))),
+        ("FNV-1a 64", format!("{:016x}", fnv1a64(bytes))),
+        (
+            "xxHash32",
+            format!("{:08x}", xxhash_rust::xxh32::xxh32(bytes, 0)),
+        ),
+        (
+            "xxHash64",
+            format!("{:016x}", xxhash_rust::xxh64::xxh64(bytes, 0)),
+        ),
+        (
+            "XXH3-64",
+            format!("{:016x}", xxhash_rust::xxh3::xxh3_64(bytes)),
+        ),
+        ("MD5", hex(&Md5::digest(bytes))),
+        ("SHA-1", hex(&Sha1::digest(bytes))),
+        ("SHA-256", hex(&Sha256::digest(bytes))),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    fn catalog(name: &str) -> &CrcParams {
+        CRC_CATALOG.iter().find(|crc| crc.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_crc_catalog_check_values() {
+        let expected: &[(&str, u64)] = &[
+            ("CRC-8/SMBUS", 0xf4),
+            ("CRC-8/MAXIM-DOW", 0xa1),
+            ("CRC-8/AUTOSAR", 0xdf),
+            ("CRC-16/ARC", 0xbb3d),
+            ("CRC-16/IBM-3740", 0x29b1),
+            ("CRC-16/XMODEM", 0x31c3),
+            ("CRC-16/KERMIT", 0x2189),
+            ("CRC-16/MODBUS", 0x4b37),
+            ("CRC-32/ISO-HDLC", 0xcbf43926),
+            ("CRC-32/ISCSI", 0xe3069283),
+            ("CRC-32/BZIP2", 0xfc891918),
+            ("CRC-32/MPEG-2", 0x0376e6e7),
+            ("CRC-64/ECMA-182", 0x6c40df5f0b497347),
+            ("CRC-64/XZ", 0x995dc9bbdf1939fa),
+            ("CRC-64/GO-ISO", 0xb90956c775a41001),
+        ];
+        assert_eq!(expected.len(), CRC_CATALOG.len());
+        for (name, check) in expected {
+            assert_eq!(catalog(name).checksum(CHECK), *check, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_crc_custom_width() {
+        // CRC-5/USB from the catalogue, a width that is not a multiple of eight.
+        let crc5 = CrcParams {
+            name: "CRC-5/USB".to_string(),
+            width: 5,
+            poly: 0x05,
+            init: 0x1f,
+            refin: true,
+            refout: true,
+            xorout: 0x1f,
+        };
+        assert_eq!(crc5.validate(), Ok(()));
+        assert_eq!(crc5.checksum(CHECK), 0x19);
+        assert_eq!(crc5.format(CHECK), "19");
+    }
+
+    #[test]
+    fn test_crc_validate() {
+        let mut crc = catalog("CRC-8/SMBUS").clone();
+        crc.poly = 0x107;
+        assert!(crc.validate().is_err());
+        crc.width = 0;
+        assert!(crc.validate().is_err());
+    }
+
+    #[test]
+    fn test_crc_config_hex_strings() {
+        let config: ChecksumConfig = json5::from_str(
+            r#"{ custom_crc: [{ name: "mine", width: 16, poly: "0x1021", init: 65535 }] }"#,
+        )
+        .unwrap();
+        assert_eq!(config.crc, ChecksumConfig::default_crc());
+        assert_eq!(config.custom_crc[0].poly, 0x1021);
+        assert_eq!(config.custom_crc[0].init, 0xffff);
+        assert_eq!(config.algorithms().len(), CRC_CATALOG.len() + 1);
+    }
+
+    #[test]
+    fn test_digests() {
+        let digests = digests(b"abcde");
+        let digest = |name| &digests.iter().find(|(n, _)| *n == name).unwrap().1;
+        assert_eq!(digest("Adler-32"), "05c801f0");
+        assert_eq!(digest("Fletcher-16"), "c8f0");
+        assert_eq!(digest("Fletcher-32"), "f04fc729");
+        assert_eq!(digest("FNV-1a 32"), "749bcf08");
+        assert_eq!(digest("MD5"), "ab56b4d92b40713acc5af89985d4b786");
+        assert_eq!(digest("SHA-1"), "03de6c570bfe24bfc328ccd7ca46b76eadaf4334");
+    }
+}
@@ -1,10 +1,16 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
 
 use crate::config::{get_config_dir, get_data_dir};
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
 pub struct Cli {
+    /// Open FILE, its bytes become the buffer
+    #[arg(value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
     /// Tick rate, i.e. number of ticks per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 4.0)]
     pub tick_rate: f64,
@@ -12,6 +18,45 @@ pub struct Cli {
     /// Frame rate, i.e. number of frames per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 60.0)]
     pub frame_rate: f64,
+
+    /// Compare the buffer with the bytes of FILE in the Compare mode
+    #[arg(long, value_name = "FILE")]
+    pub compare: Option<PathBuf>,
+
+    /// Click, drag and scroll with the mouse, regardless of the configuration
+    #[arg(long)]
+    pub mouse: bool,
+
+    /// Write the buffer the session starts with and its events to FILE, one JSON object per line
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Replay the session recorded in FILE from the buffer it started with, the terminal keeps
+    /// working alongside
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
+    /// Write the log to FILE instead of a new file per session in the data directory
+    #[arg(long, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Report every invalid entry of the configuration files
+    Check,
 }
 
 const VERSION_MESSAGE: &str = concat!(
@@ -2,11 +2,12 @@ use color_eyre::Result;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{
     Frame,
-    layout::{Rect, Size},
+    layout::{Constraint, Rect, Size},
 };
+use serde_json::Value;
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{action::Action, config::Config, tui::Event};
+use crate::{action::Action, config::Config, layout::Panel, tui::Event};
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
@@ -39,6 +40,40 @@ pub trait Component {
         let _ = config; // to appease clippy
         Ok(())
     }
+    /// The panel under which the component is placed in a layout.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Panel>` - The panel, or none for overlays that are drawn on top of every layout.
+    fn panel(&self) -> Option<Panel> {
+        None
+    }
+    /// The constraint the component requests within its split of the layout.
+    ///
+    /// # Returns
+    ///
+    /// * `Constraint` - The requested constraint, an equal share of the split by default.
+    fn constraint(&self) -> Constraint {
+        Constraint::Fill(1)
+    }
+    /// Whether the component currently takes keyboard input away from the focused panel, e.g.
+    /// an open popup.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if key events should be sent to this component.
+    fn captures_focus(&self) -> bool {
+        false
+    }
+    /// Whether key events should bypass the keybindings while the component has focus, e.g. for
+    /// free text input.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if keys should only be passed to `handle_key_event`.
+    fn captures_keys(&self) -> bool {
+        false
+    }
     /// Initialize the component with a specified area if necessary.
     ///
     /// # Arguments
@@ -108,6 +143,27 @@ pub trait Component {
         let _ = action; // to appease clippy
         Ok(None)
     }
+    /// The selection of the component, saved with a workspace.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Value>` - The selection, or none if the component has nothing to save.
+    fn selection(&self) -> Option<Value> {
+        None
+    }
+    /// Restore a selection saved with a workspace, once the buffer of the workspace is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - A selection returned by `selection`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn select(&mut self, selection: Value) -> Result<()> {
+        let _ = selection; // to appease clippy
+        Ok(())
+    }
     /// Render the component on the screen. (REQUIRED)
     ///
     /// # Arguments
@@ -0,0 +1,145 @@
+use ratatui::{prelude::*, widgets::*};
+
+/// Widget laying out the bits of a buffer, most significant bit first, eight bits per byte.
+///
+/// Bytes wrap as a whole when the area is too narrow for the buffer. Only the lines from the
+/// scroll offset that fit in the area are drawn.
+pub struct BitGrid<'a> {
+    bytes: &'a [u8],
+    /// The first line drawn.
+    scroll: usize,
+    one: Style,
+    zero: Style,
+}
+
+/// Width of one byte in the grid including the separating space.
+const BYTE_WIDTH: u16 = 9;
+
+impl<'a> BitGrid<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            scroll: 0,
+            one: Style::new().bold(),
+            zero: Style::new().dim(),
+        }
+    }
+
+    /// Style of the bits that are set.
+    pub fn one_style(mut self, style: Style) -> Self {
+        self.one = style;
+        self
+    }
+
+    /// Start drawing at line `scroll`.
+    pub fn scroll(mut self, scroll: usize) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    fn bytes_per_line(width: u16) -> usize {
+        usize::from((width + 1) / BYTE_WIDTH).max(1)
+    }
+
+    /// The byte index and bit number (0 is the least significant bit) drawn at `position` when
+    /// the grid is rendered in `area`.
+    pub fn bit_at(&self, area: Rect, position: Position) -> Option<(usize, u8)> {
+        if !area.contains(position) {
+            return None;
+        }
+        let column = position.x - area.x;
+        let (byte_column, bit_column) = (column / BYTE_WIDTH, column % BYTE_WIDTH);
+        let bytes_per_line = Self::bytes_per_line(area.width);
+        // The last column of a byte is the space separating it from the next one.
+        if bit_column >= 8 || usize::from(byte_column) >= bytes_per_line {
+            return None;
+        }
+        let line = usize::from(position.y - area.y) + self.scroll;
+        let byte = line * bytes_per_line + usize::from(byte_column);
+        (byte < self.bytes.len()).then_some((byte, 7 - bit_column as u8))
+    }
+
+    /// Number of lines needed to render the whole grid in the given width.
+    pub fn line_count(&self, width: u16) -> usize {
+        self.bytes
+            .len()
+            .div_ceil(Self::bytes_per_line(width))
+            .max(1)
+    }
+
+    /// Number of lines needed to render the grid in the given width, from the scroll offset.
+    pub fn height(&self, width: u16) -> u16 {
+        let lines = self.line_count(width).saturating_sub(self.scroll).max(1);
+        u16::try_from(lines).unwrap_or(u16::MAX)
+    }
+}
+
+impl Widget for BitGrid<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .bytes
+            .chunks(Self::bytes_per_line(area.width))
+            .skip(self.scroll)
+            .take(usize::from(area.height))
+            .map(|chunk| {
+                let mut spans = Vec::with_capacity(chunk.len() * 9);
+                for (i, byte) in chunk.iter().enumerate() {
+                    if i > 0 {
+                        spans.push(Span::raw(" "));
+                    }
+                    for bit in (0..8).rev() {
+                        if byte & (1 << bit) != 0 {
+                            spans.push(Span::styled("1", self.one));
+                        } else {
+                            spans.push(Span::styled("0", self.zero));
+                        }
+                    }
+                }
+                Line::from(spans)
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_bit_at() {
+        let bytes = [0u8; 3];
+        let grid = BitGrid::new(&bytes);
+        // Two bytes per line: "00000000 00000000"
+        let area = Rect::new(2, 3, 17, 2);
+        assert_eq!(grid.bit_at(area, Position::new(2, 3)), Some((0, 7)));
+        assert_eq!(grid.bit_at(area, Position::new(9, 3)), Some((0, 0)));
+        assert_eq!(grid.bit_at(area, Position::new(10, 3)), None);
+        assert_eq!(grid.bit_at(area, Position::new(11, 3)), Some((1, 7)));
+        assert_eq!(grid.bit_at(area, Position::new(4, 4)), Some((2, 5)));
+        assert_eq!(grid.bit_at(area, Position::new(11, 4)), None);
+        assert_eq!(grid.bit_at(area, Position::new(2, 5)), None);
+
+        let grid = grid.scroll(1);
+        assert_eq!(grid.bit_at(area, Position::new(2, 3)), Some((2, 7)));
+        assert_eq!(grid.bit_at(area, Position::new(2, 4)), None);
+    }
+
+    #[test]
+    fn test_draws_the_lines_from_the_scroll_offset() {
+        let bytes: Vec<u8> = (0..=255).cycle().take(1 << 20).collect();
+        let grid = BitGrid::new(&bytes).scroll(3);
+        // Eight bytes per line.
+        assert_eq!(grid.line_count(71), 1 << 17);
+        assert_eq!(grid.height(71), u16::MAX);
+        let area = Rect::new(0, 0, 71, 1);
+        let mut buf = Buffer::empty(area);
+        grid.render(area, &mut buf);
+        assert_eq!(
+            buf[(0, 0)].symbol().to_string() + buf[(7, 0)].symbol() + buf[(70, 0)].symbol(),
+            "001"
+        );
+    }
+}
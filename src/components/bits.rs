@@ -0,0 +1,88 @@
+use color_eyre::Result;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::component::Component;
+use crate::components::bit_grid::BitGrid;
+use crate::layout::Panel;
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+/// Bit grid of the current buffer.
+#[derive(Default)]
+pub struct Bits {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    buffer: Vec<u8>,
+    /// The first line of the grid drawn.
+    scroll: usize,
+    /// Where the grid was drawn last, for mouse clicks.
+    grid_area: Rect,
+}
+
+impl Bits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Component for Bits {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn panel(&self) -> Option<Panel> {
+        Some(Panel::Bits)
+    }
+
+    /// Clicking a bit toggles it.
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return Ok(None);
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        let grid = BitGrid::new(&self.buffer).scroll(self.scroll);
+        let Some((byte, bit)) = grid.bit_at(self.grid_area, position) else {
+            return Ok(None);
+        };
+        let mut buffer = self.buffer.clone();
+        buffer[byte] ^= 1 << bit;
+        Ok(Some(Action::SetBuffer(buffer)))
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetBuffer(bytes) => self.buffer = bytes,
+            // Clamped to the lines of the grid when drawn.
+            Action::ScrollDown => self.scroll = self.scroll.saturating_add(1),
+            Action::ScrollUp => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Bits ({})", self.buffer.len() * 8));
+        self.grid_area = block.inner(area);
+        let grid = BitGrid::new(&self.buffer);
+        self.scroll = self
+            .scroll
+            .min(grid.line_count(self.grid_area.width).saturating_sub(1));
+        let grid = grid
+            .scroll(self.scroll)
+            .one_style(self.config.theme.style(Role::HighlightBit));
+        frame.render_widget(grid, self.grid_area);
+        frame.render_widget(block, area);
+        Ok(())
+    }
+}
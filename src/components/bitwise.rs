@@ -0,0 +1,187 @@
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::bitops::Operation;
+use crate::component::Component;
+use crate::components::bit_grid::BitGrid;
+use crate::components::home::parse_hex;
+use crate::layout::Panel;
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+/// Workbench applying bitwise operations to the current buffer (A) and a second operand (B).
+#[derive(Default)]
+pub struct Bitwise {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    a: Vec<u8>,
+    b_input: String,
+    /// B as typed, read as a number.
+    b: Vec<u8>,
+    /// The result of every operation, updated whenever A or B change.
+    results: Vec<Vec<u8>>,
+    selected: usize,
+    /// The first line drawn of every grid.
+    scroll: usize,
+}
+
+/// The operand and operation, as saved with a workspace.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedSelection {
+    operand: String,
+    operation: Option<Operation>,
+}
+
+impl Bitwise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply every operation to the operands after one of them changed.
+    fn refresh(&mut self) {
+        self.b = parse_hex(&self.b_input);
+        self.results = Operation::iter()
+            .map(|operation| operation.apply(&self.a, &self.b))
+            .collect();
+    }
+
+    fn selected_operation(&self) -> Option<Operation> {
+        Operation::iter().nth(self.selected)
+    }
+}
+
+impl Component for Bitwise {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn panel(&self) -> Option<Panel> {
+        Some(Panel::Bitwise)
+    }
+
+    fn selection(&self) -> Option<Value> {
+        let saved = SavedSelection {
+            operand: self.b_input.clone(),
+            operation: self.selected_operation(),
+        };
+        serde_json::to_value(saved).ok()
+    }
+
+    fn select(&mut self, selection: Value) -> Result<()> {
+        let saved: SavedSelection = serde_json::from_value(selection)?;
+        // A shared workspace may have been edited by hand.
+        self.b_input = saved
+            .operand
+            .chars()
+            .filter(char::is_ascii_hexdigit)
+            .collect();
+        self.refresh();
+        self.selected = saved
+            .operation
+            .and_then(|operation| Operation::iter().position(|o| o == operation))
+            .unwrap_or(0);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetBuffer(bytes) => {
+                self.a = bytes;
+                self.refresh();
+            }
+            Action::CharacterInput(character_input) => {
+                if let Some(digit) = character_input.digit() {
+                    self.b_input
+                        .push(char::from_digit(digit.into(), 16).unwrap_or('0'));
+                    self.refresh();
+                }
+            }
+            Action::DeleteCharacter => {
+                self.b_input.pop();
+                self.refresh();
+            }
+            Action::ScrollDown => {
+                self.selected = (self.selected + 1).min(Operation::iter().count() - 1);
+            }
+            Action::ScrollUp => self.selected = self.selected.saturating_sub(1),
+            // Clamped to the lines of the longest grid when drawn.
+            Action::SelectRight => self.scroll = self.scroll.saturating_add(1),
+            Action::SelectLeft => self.scroll = self.scroll.saturating_sub(1),
+            Action::Submit => {
+                if let Some(result) = self.results.get(self.selected) {
+                    return Ok(Some(Action::SetBuffer(result.clone())));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Bitwise (enter: use result as A)");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        const LABEL_WIDTH: u16 = 12;
+        let results: Vec<(String, &[u8])> = [
+            ("A".to_string(), self.a.as_slice()),
+            (format!("B {}_", self.b_input), self.b.as_slice()),
+        ]
+        .into_iter()
+        .chain(
+            Operation::iter()
+                .zip(&self.results)
+                .map(|(operation, result)| (operation.to_string(), result.as_slice())),
+        )
+        .collect();
+
+        // Every grid gets an equal share of the lines, after the blank line below the operands.
+        let width = inner.width.saturating_sub(LABEL_WIDTH);
+        let share = (inner.height.saturating_sub(1) / results.len() as u16).max(1);
+        let line_count = results
+            .iter()
+            .map(|(_, bytes)| BitGrid::new(bytes).line_count(width))
+            .max()
+            .unwrap_or(1);
+        self.scroll = self.scroll.min(line_count.saturating_sub(1));
+
+        let mut y = inner.y;
+        for (i, (label, bytes)) in results.iter().enumerate() {
+            let grid = BitGrid::new(bytes)
+                .scroll(self.scroll)
+                .one_style(self.config.theme.style(Role::HighlightBit));
+            let height = grid.height(width).min(share);
+            if y.saturating_add(height) > inner.bottom() {
+                break;
+            }
+            let row = Rect::new(inner.x, y, inner.width, height);
+            let [label_area, grid_area] =
+                Layout::horizontal([Constraint::Length(LABEL_WIDTH), Constraint::Min(0)])
+                    .areas(row);
+            let is_selected = i >= 2 && i - 2 == self.selected;
+            let label_style = if is_selected {
+                self.config.theme.style(Role::Selected)
+            } else {
+                Style::new()
+            };
+            frame.render_widget(Span::styled(label.as_str(), label_style), label_area);
+            frame.render_widget(grid, grid_area);
+            // Leave a blank line after the operands.
+            y = y.saturating_add(height + u16::from(i == 1));
+        }
+        Ok(())
+    }
+}
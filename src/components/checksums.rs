@@ -0,0 +1,196 @@
+use std::ops::Range;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::checksum::{CrcParams, digests};
+use crate::component::Component;
+use crate::components::popup_area;
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+/// Popup listing checksums and hashes of the current buffer, or of the bytes selected in the hex
+/// view.
+///
+/// Each CRC row can be switched between the algorithms of the same width.
+#[derive(Default)]
+pub struct Checksums {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    visible: bool,
+    buffer: Vec<u8>,
+    /// The bytes selected in the hex view, hashed instead of the whole buffer.
+    selection: Option<Range<usize>>,
+    algorithms: Vec<CrcParams>,
+    crc_rows: Vec<CrcParams>,
+    selected_row: usize,
+    /// The names and values of every row, computed while the popup is shown and cleared when
+    /// the buffer or the algorithms change.
+    rows: Option<Vec<(String, String)>>,
+}
+
+impl Checksums {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Switch the selected CRC row to the next or previous algorithm of the same width.
+    fn cycle_crc(&mut self, forward: bool) {
+        let Some(current) = self.crc_rows.get(self.selected_row) else {
+            return;
+        };
+        let candidates: Vec<_> = self
+            .algorithms
+            .iter()
+            .filter(|crc| crc.width == current.width)
+            .collect();
+        let position = candidates
+            .iter()
+            .position(|crc| crc.name == current.name)
+            .unwrap_or(0);
+        let next = if forward {
+            (position + 1) % candidates.len()
+        } else {
+            (position + candidates.len() - 1) % candidates.len()
+        };
+        self.crc_rows[self.selected_row] = candidates[next].clone();
+        self.rows = None;
+    }
+
+    /// The bytes the checksums are computed over.
+    fn bytes(&self) -> &[u8] {
+        self.selection
+            .clone()
+            .and_then(|selection| self.buffer.get(selection))
+            .unwrap_or(&self.buffer)
+    }
+
+    fn title(&self) -> String {
+        match &self.selection {
+            Some(selection) if self.buffer.get(selection.clone()).is_some() => format!(
+                "Checksums of {:#x}..{:#x} ({} bytes)",
+                selection.start,
+                selection.end,
+                selection.len()
+            ),
+            _ => format!("Checksums ({} bytes)", self.buffer.len()),
+        }
+    }
+
+    /// Compute the rows if they are shown and out of date, hashing a large buffer takes a while.
+    fn refresh(&mut self) {
+        if !self.visible || self.rows.is_some() {
+            return;
+        }
+        let crc_rows = self
+            .crc_rows
+            .iter()
+            .map(|crc| (format!("◂ {} ▸", crc.name), crc.format(self.bytes())));
+        let digest_rows = digests(self.bytes())
+            .into_iter()
+            .map(|(name, digest)| (name.to_string(), digest));
+        self.rows = Some(crc_rows.chain(digest_rows).collect());
+    }
+}
+
+impl Component for Checksums {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.algorithms = config.checksum.algorithms();
+        self.crc_rows = config
+            .checksum
+            .crc
+            .iter()
+            .filter_map(|name| self.algorithms.iter().find(|crc| &crc.name == name))
+            .cloned()
+            .collect();
+        self.selected_row = 0;
+        self.rows = None;
+        self.config = config;
+        self.refresh();
+        Ok(())
+    }
+
+    fn captures_focus(&self) -> bool {
+        self.visible
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetBuffer(bytes) => {
+                self.buffer = bytes;
+                self.selection = None;
+                self.rows = None;
+            }
+            Action::SetSelection(selection) => {
+                self.selection = selection;
+                self.rows = None;
+            }
+            Action::ToggleChecksums => self.visible = !self.visible,
+            Action::SelectRight => self.cycle_crc(true),
+            Action::SelectLeft => self.cycle_crc(false),
+            Action::ScrollDown => {
+                self.selected_row = (self.selected_row + 1).min(self.crc_rows.len().max(1) - 1);
+            }
+            Action::ScrollUp => self.selected_row = self.selected_row.saturating_sub(1),
+            _ => {}
+        }
+        self.refresh();
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let area = popup_area(area, 80, 80);
+        frame.render_widget(Clear, area);
+
+        let rows = self
+            .rows
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let row = Row::new([name.as_str(), value.as_str()]);
+                if i == self.selected_row && i < self.crc_rows.len() {
+                    row.style(self.config.theme.style(Role::Selected))
+                } else {
+                    row
+                }
+            });
+        let table = Table::new(rows, [Constraint::Length(22), Constraint::Min(0)])
+            .block(Block::default().borders(Borders::ALL).title(self.title()));
+        frame.render_widget(table, area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_hashes_the_selection() -> Result<()> {
+        let mut checksums = Checksums::new();
+        checksums.update(Action::ToggleChecksums)?;
+        checksums.update(Action::SetBuffer(b"xx123456789".to_vec()))?;
+        checksums.update(Action::SetSelection(Some(2..11)))?;
+        assert_eq!(checksums.title(), "Checksums of 0x2..0xb (9 bytes)");
+        let rows = checksums.rows.clone().unwrap_or_default();
+        // The check value of Adler-32.
+        assert!(rows.contains(&("Adler-32".to_string(), "091e01de".to_string())));
+
+        checksums.update(Action::SetSelection(None))?;
+        assert_eq!(checksums.title(), "Checksums (11 bytes)");
+        Ok(())
+    }
+}
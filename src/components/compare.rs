@@ -0,0 +1,482 @@
+use std::{fs, path::PathBuf};
+
+use color_eyre::{Result, eyre::WrapErr};
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::component::Component;
+use crate::diff::{self, MAX_SHIFT, Summary};
+use crate::layout::Panel;
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+const BYTES_PER_ROW: usize = 8;
+
+/// Bytes of B shown in the title, the last ones typed.
+const TITLE_BYTES: usize = 8;
+
+/// The second buffer, shift and row, as saved with a workspace. B read from a file is saved as
+/// its path, typed digits as they are.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedSelection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    operand: String,
+    shift: isize,
+    row: usize,
+}
+
+/// The current buffer (A) and a second one (B) side by side, with the bytes that differ
+/// highlighted and the bits of the selected row below.
+///
+/// B is typed while the panel is focused or read from a file. Left and right shift B against A,
+/// enter shifts it to the best alignment.
+#[derive(Default)]
+pub struct Compare {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    a: Vec<u8>,
+    b: Vec<u8>,
+    /// The file B was read from, until it is edited.
+    b_file: Option<PathBuf>,
+    /// The first digit of a byte being typed into B.
+    pending: Option<u8>,
+    /// Offset of A at which B starts, negative when B starts first.
+    shift: isize,
+    /// The selected row, its bits are shown.
+    row: usize,
+    /// What differs at the current shift, updated whenever A, B or the shift change.
+    summary: Summary,
+}
+
+impl Compare {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn row_count(&self) -> usize {
+        diff::positions(self.a.len(), self.b.len(), self.shift)
+            .len()
+            .div_ceil(BYTES_PER_ROW)
+    }
+
+    /// Shift B, or bring the summary and selected row up to date after A or B changed.
+    fn set_shift(&mut self, shift: isize) {
+        self.shift = shift;
+        self.summary = diff::summarize(&self.a, &self.b, shift);
+        self.row = self.row.min(self.row_count().saturating_sub(1));
+    }
+
+    /// Type a hex digit into B, every second one completes a byte.
+    fn push_digit(&mut self, digit: u8) {
+        match self.pending.take() {
+            Some(high) => self.b.push(high << 4 | digit),
+            None => self.pending = Some(digit),
+        }
+    }
+
+    /// B as hex digits, the pending one included.
+    fn b_digits(&self, bytes: &[u8]) -> String {
+        let mut digits: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        if let Some(digit) = self.pending {
+            digits.push(char::from_digit(digit.into(), 16).unwrap_or('0'));
+        }
+        digits
+    }
+
+    fn title(&self) -> String {
+        if let Some(file) = &self.b_file {
+            return format!("Compare with {}", file.display());
+        }
+        let tail = &self.b[self.b.len().saturating_sub(TITLE_BYTES)..];
+        let ellipsis = if tail.len() < self.b.len() { "…" } else { "" };
+        format!("Compare with B {ellipsis}{}_", self.b_digits(tail))
+    }
+
+    fn summary_line(&self, summary: &Summary) -> Line<'static> {
+        let shift = format!("shift {:+}", self.shift);
+        if summary.is_identical() {
+            return Line::raw(format!("Identical, {} bytes, {shift}", self.a.len()));
+        }
+        let mut text = format!("{} of {} bytes differ", summary.differing, summary.compared);
+        if let Some(first) = summary.first {
+            text += &format!(", first at {first:#x}");
+        }
+        text += &format!(", Hamming distance {}", summary.hamming);
+        if summary.unmatched > 0 {
+            text += &format!(", {} unmatched", summary.unmatched);
+        }
+        text += &format!(", {shift}");
+        Line::styled(text, self.config.theme.style(Role::Difference))
+    }
+
+    /// One side of a row: the offset of its first byte and the bytes in hex. Bytes that differ
+    /// or have no counterpart are highlighted.
+    fn hex_side(
+        &self,
+        pairs: &[(Option<u8>, Option<u8>)],
+        offset: Option<usize>,
+        of_a: bool,
+    ) -> Vec<Span<'static>> {
+        let difference = self.config.theme.style(Role::Difference);
+        let mut spans = vec![Span::raw(match offset {
+            Some(offset) => format!("{offset:08x}  "),
+            None => " ".repeat(10),
+        })];
+        for (i, (x, y)) in pairs.iter().enumerate() {
+            let (byte, other) = if of_a { (x, y) } else { (y, x) };
+            if i > 0 {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(match byte {
+                Some(byte) if other == &Some(*byte) => Span::raw(format!("{byte:02x}")),
+                Some(byte) => Span::styled(format!("{byte:02x}"), difference),
+                None => Span::raw("  "),
+            });
+        }
+        let padding = (BYTES_PER_ROW - pairs.len()) * 3;
+        spans.push(Span::raw(" ".repeat(padding)));
+        spans
+    }
+
+    /// The bits of one side of a row, the ones that differ highlighted.
+    fn bits_line(
+        &self,
+        label: &str,
+        pairs: &[(Option<u8>, Option<u8>)],
+        of_a: bool,
+    ) -> Line<'static> {
+        let difference = self.config.theme.style(Role::Difference);
+        let mut spans = vec![Span::raw(format!("{label} "))];
+        for (x, y) in pairs {
+            let (byte, other) = if of_a { (x, y) } else { (y, x) };
+            let Some(byte) = byte else {
+                spans.push(Span::raw(" ".repeat(9)));
+                continue;
+            };
+            for bit in (0..8).rev() {
+                let value = byte >> bit & 1;
+                let differs = other.is_none_or(|other| other >> bit & 1 != value);
+                let text = if value == 1 { "1" } else { "0" };
+                spans.push(if differs {
+                    Span::styled(text, difference)
+                } else {
+                    Span::raw(text)
+                });
+            }
+            spans.push(Span::raw(" "));
+        }
+        Line::from(spans)
+    }
+}
+
+impl Component for Compare {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn panel(&self) -> Option<Panel> {
+        Some(Panel::Compare)
+    }
+
+    fn selection(&self) -> Option<Value> {
+        let saved = SavedSelection {
+            file: self.b_file.clone(),
+            operand: match self.b_file {
+                Some(_) => String::new(),
+                None => self.b_digits(&self.b),
+            },
+            shift: self.shift,
+            row: self.row,
+        };
+        serde_json::to_value(saved).ok()
+    }
+
+    fn select(&mut self, selection: Value) -> Result<()> {
+        let saved: SavedSelection = serde_json::from_value(selection)?;
+        // A shared workspace may have been edited by hand.
+        self.b.clear();
+        self.pending = None;
+        match &saved.file {
+            Some(file) => {
+                self.b =
+                    fs::read(file).wrap_err_with(|| format!("Cannot open {}", file.display()))?;
+            }
+            None => {
+                for digit in saved.operand.chars().filter_map(|c| c.to_digit(16)) {
+                    self.push_digit(digit as u8);
+                }
+            }
+        }
+        self.b_file = saved.file;
+        self.row = saved.row;
+        self.set_shift(saved.shift);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetBuffer(bytes) => {
+                self.a = bytes;
+                self.set_shift(self.shift);
+            }
+            Action::SetCompareBuffer(bytes, file) => {
+                self.b = bytes;
+                self.b_file = file;
+                self.pending = None;
+                self.row = 0;
+                self.set_shift(0);
+            }
+            Action::CharacterInput(character_input) => {
+                if let Some(digit) = character_input.digit() {
+                    self.b_file = None;
+                    self.push_digit(digit);
+                    self.set_shift(self.shift);
+                }
+            }
+            Action::DeleteCharacter => {
+                self.b_file = None;
+                // Delete the last digit typed, the low one of the last byte if none is pending.
+                if self.pending.take().is_none() {
+                    self.pending = self.b.pop().map(|byte| byte >> 4);
+                }
+                self.set_shift(self.shift);
+            }
+            Action::SelectLeft => self.set_shift(self.shift - 1),
+            Action::SelectRight => self.set_shift(self.shift + 1),
+            Action::ScrollDown => {
+                self.row = (self.row + 1).min(self.row_count().saturating_sub(1));
+            }
+            Action::ScrollUp => self.row = self.row.saturating_sub(1),
+            Action::Submit => {
+                let shift = diff::best_shift(&self.a, &self.b, MAX_SHIFT);
+                self.set_shift(shift);
+                return Ok(Some(Action::Info(format!("Aligned with shift {shift:+}"))));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let block = Block::default().borders(Borders::ALL).title(self.title());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let (a, b) = (self.a.as_slice(), self.b.as_slice());
+        let positions = diff::positions(a.len(), b.len(), self.shift);
+        // The positions of a row, only the visible rows are looked at.
+        let row_positions = |row: usize| {
+            let start = positions.start + (row * BYTES_PER_ROW) as isize;
+            start..(start + BYTES_PER_ROW as isize).min(positions.end)
+        };
+        let pairs = |row: usize| -> Vec<(Option<u8>, Option<u8>)> {
+            row_positions(row)
+                .map(|position| diff::at(a, b, self.shift, position))
+                .collect()
+        };
+        // The offset in A and in B of the first byte of a row that has one.
+        let offsets = |row: usize| {
+            let row = row_positions(row);
+            let a_offset = row
+                .clone()
+                .find(|p| usize::try_from(*p).is_ok_and(|p| p < a.len()));
+            let b_offset = row
+                .map(|p| p - self.shift)
+                .find(|p| usize::try_from(*p).is_ok_and(|p| p < b.len()));
+            (a_offset.map(|a| a as usize), b_offset.map(|b| b as usize))
+        };
+
+        let [summary_area, header_area, rows_area, bits_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
+        .areas(inner);
+        frame.render_widget(self.summary_line(&self.summary), summary_area);
+        frame.render_widget(
+            Line::raw(format!(
+                "{:<35}│ B, {} bytes",
+                format!("A, {} bytes", a.len()),
+                b.len()
+            ))
+            .bold(),
+            header_area,
+        );
+
+        let height = usize::from(rows_area.height);
+        let top = (self.row + 1).saturating_sub(height);
+        let selected_style = self.config.theme.style(Role::Selected);
+        let lines: Vec<Line> = (top..self.row_count().min(top + height))
+            .map(|i| {
+                let pairs = pairs(i);
+                let (a_offset, b_offset) = offsets(i);
+                let mut spans = self.hex_side(&pairs, a_offset, true);
+                spans.push(Span::raw("  │ "));
+                spans.extend(self.hex_side(&pairs, b_offset, false));
+                let line = Line::from(spans);
+                if i == self.row {
+                    line.patch_style(selected_style)
+                } else {
+                    line
+                }
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), rows_area);
+
+        if self.row < self.row_count() {
+            let pairs = pairs(self.row);
+            let bits = vec![
+                self.bits_line("A", &pairs, true),
+                self.bits_line("B", &pairs, false),
+            ];
+            frame.render_widget(Paragraph::new(bits), bits_area);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::action::CharacterInput;
+
+    fn render(compare: &mut Compare) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(80, 8)).unwrap();
+        terminal
+            .draw(|frame| compare.draw(frame, frame.area()).unwrap())
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..8)
+            .map(|y| {
+                let row: String = (0..80).map(|x| buffer[(x, y)].symbol()).collect();
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_shows_differences_side_by_side() -> Result<()> {
+        let mut compare = Compare::new();
+        compare.update(Action::SetBuffer(vec![0xde, 0xad, 0xbe, 0xef]))?;
+        compare.update(Action::SetCompareBuffer(
+            vec![0xaa, 0xde, 0xad, 0xbe, 0xee],
+            None,
+        ))?;
+        let screen = render(&mut compare);
+        assert_eq!(
+            screen[1],
+            "│4 of 4 bytes differ, first at 0x0, Hamming distance 15, 1 unmatched, shift +0 │"
+        );
+        assert_eq!(
+            screen[3],
+            "│00000000  de ad be ef              │ 00000000  aa de ad be ee                 │"
+        );
+        assert!(screen[5].starts_with("│A 11011110 10101101 10111110 11101111  "));
+
+        // B starts one byte before A.
+        compare.update(Action::SelectLeft)?;
+        let screen = render(&mut compare);
+        assert!(
+            screen[1].starts_with("│1 of 4 bytes differ, first at 0x3, Hamming distance 1, "),
+            "{}",
+            screen[1]
+        );
+        assert_eq!(
+            screen[3],
+            "│00000000     de ad be ef           │ 00000000  aa de ad be ee                 │"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_digits_complete_bytes() -> Result<()> {
+        let mut compare = Compare::new();
+        for input in [
+            CharacterInput::Ten,
+            CharacterInput::Eleven,
+            CharacterInput::Twelve,
+        ] {
+            compare.update(Action::CharacterInput(input))?;
+        }
+        assert_eq!(
+            (compare.b.as_slice(), compare.pending),
+            (&[0xab][..], Some(0xc))
+        );
+        assert_eq!(compare.title(), "Compare with B abc_");
+        compare.update(Action::DeleteCharacter)?;
+        compare.update(Action::DeleteCharacter)?;
+        assert_eq!(
+            (compare.b.as_slice(), compare.pending),
+            (&[][..], Some(0xa))
+        );
+
+        // Only the end of a long buffer is shown.
+        compare.update(Action::SetCompareBuffer((0..100).collect(), None))?;
+        assert_eq!(compare.title(), "Compare with B …5c5d5e5f60616263_");
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_is_saved_by_path() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("b.bin");
+        fs::write(&path, [0xde, 0xad])?;
+        let mut compare = Compare::new();
+        compare.update(Action::SetCompareBuffer(
+            vec![0xde, 0xad],
+            Some(path.clone()),
+        ))?;
+        compare.update(Action::SelectRight)?;
+        let saved = compare.selection().unwrap();
+        assert_eq!(
+            saved,
+            serde_json::json!({ "file": path, "shift": 1, "row": 0 })
+        );
+
+        let mut restored = Compare::new();
+        restored.select(saved)?;
+        assert_eq!(restored.b, [0xde, 0xad]);
+        assert_eq!(restored.shift, 1);
+
+        // Edited, B no longer matches the file.
+        restored.update(Action::CharacterInput(CharacterInput::One))?;
+        assert_eq!(
+            restored.selection().unwrap()["operand"],
+            serde_json::json!("dead1")
+        );
+        fs::remove_file(&path)?;
+        let missing = serde_json::json!({ "file": path });
+        assert!(Compare::new().select(missing).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_aligns() -> Result<()> {
+        let mut compare = Compare::new();
+        compare.update(Action::SetBuffer(vec![1, 2, 3, 4]))?;
+        compare.update(Action::SetCompareBuffer(vec![0xff, 0xff, 1, 2, 3, 4], None))?;
+        let action = compare.update(Action::Submit)?;
+        assert_eq!(
+            action,
+            Some(Action::Info("Aligned with shift -2".to_string()))
+        );
+        assert_eq!(compare.shift, -2);
+        Ok(())
+    }
+}
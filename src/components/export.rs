@@ -0,0 +1,98 @@
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::component::Component;
+use crate::components::popup_area;
+use crate::export::{LiteralFormat, export};
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+/// Popup rendering the current buffer as a source-code literal.
+#[derive(Default)]
+pub struct Export {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    visible: bool,
+    buffer: Vec<u8>,
+    format: LiteralFormat,
+    scroll: u16,
+}
+
+impl Export {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn cycle_format(&mut self, forward: bool) {
+        let formats: Vec<_> = LiteralFormat::iter().collect();
+        let current = formats.iter().position(|f| *f == self.format).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % formats.len()
+        } else {
+            (current + formats.len() - 1) % formats.len()
+        };
+        self.format = formats[next];
+        self.scroll = 0;
+    }
+}
+
+impl Component for Export {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.format = config.export.format;
+        self.config = config;
+        Ok(())
+    }
+
+    fn captures_focus(&self) -> bool {
+        self.visible
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetBuffer(bytes) => self.buffer = bytes,
+            Action::ToggleExport => self.visible = !self.visible,
+            Action::SelectRight => self.cycle_format(true),
+            Action::SelectLeft => self.cycle_format(false),
+            Action::ScrollDown => self.scroll = self.scroll.saturating_add(1),
+            Action::ScrollUp => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let area = popup_area(area, 80, 60);
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Export ({} bytes)", self.buffer.len()));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [tabs, body] =
+            Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+        let titles = LiteralFormat::iter().map(|f| f.to_string());
+        let selected = LiteralFormat::iter().position(|f| f == self.format);
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(selected)
+                .highlight_style(self.config.theme.style(Role::HeaderActive))
+                .block(Block::default().borders(Borders::BOTTOM)),
+            tabs,
+        );
+        let literal = export(&self.buffer, self.format, &self.config.export);
+        frame.render_widget(Paragraph::new(literal).scroll((self.scroll, 0)), body);
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::app::Mode;
+use crate::component::Component;
+use crate::components::popup_area;
+use crate::config::key_sequence_to_string;
+use crate::{action::Action, config::Config};
+
+/// Popup listing the keybindings of the active mode.
+#[derive(Default)]
+pub struct Help {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    visible: bool,
+    mode: Mode,
+    scroll: usize,
+}
+
+impl Help {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every binding of the active mode as `(action, keys, description)`, bindings with the same
+    /// description share a row.
+    fn rows(&self) -> Vec<(String, String, String)> {
+        let mut groups: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+        let Some(keymap) = self.config.keybindings.get(&self.mode) else {
+            return Vec::new();
+        };
+        for (keys, action) in keymap.iter() {
+            groups
+                .entry((action.to_string(), action.description()))
+                .or_default()
+                .push(key_sequence_to_string(keys));
+        }
+        groups
+            .into_iter()
+            .map(|((action, description), mut keys)| {
+                keys.sort();
+                (action, keys.join(" "), description)
+            })
+            .collect()
+    }
+}
+
+impl Component for Help {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn captures_focus(&self) -> bool {
+        self.visible
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Help => {
+                self.visible = !self.visible;
+                self.scroll = 0;
+            }
+            Action::SwitchMode(mode) => {
+                self.mode = mode;
+                self.scroll = 0;
+            }
+            Action::ScrollDown => self.scroll = self.scroll.saturating_add(1),
+            Action::ScrollUp => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let area = popup_area(area, 80, 80);
+        frame.render_widget(Clear, area);
+        let rows = self.rows();
+        self.scroll = self.scroll.min(rows.len().saturating_sub(1));
+        let table = Table::new(
+            rows.into_iter()
+                .skip(self.scroll)
+                .map(|(action, keys, description)| Row::new([keys, action, description])),
+            [
+                Constraint::Percentage(40),
+                Constraint::Length(16),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["Keys", "Action", "Description"]).bold())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Help: {} mode", self.mode)),
+        );
+        frame.render_widget(table, area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_rows_group_bindings_by_action() -> Result<()> {
+        let mut help = Help::new();
+        help.register_config_handler(Config::new()?)?;
+        let rows = help.rows();
+        let digits = rows
+            .iter()
+            .find(|(action, ..)| action == "CharacterInput")
+            .unwrap();
+        assert_eq!(digits.2, "Type a hex digit");
+        assert_eq!(digits.1.split(' ').count(), 16);
+        let quit = rows.iter().find(|(action, ..)| action == "Quit").unwrap();
+        assert_eq!(quit.1, "<ctrl-c> <ctrl-d> <q>");
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+use std::ops::RangeInclusive;
+
+use color_eyre::Result;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::component::Component;
+use crate::layout::Panel;
+use crate::theme::Role;
+use crate::{
+    action::{Action, Bookmark},
+    config::Config,
+};
+
+const BYTES_PER_LINE: usize = 16;
+
+/// Classic hex dump of the current buffer with offsets and an ASCII gutter.
+#[derive(Default)]
+pub struct HexView {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    buffer: Vec<u8>,
+    scroll: usize,
+    /// Offsets of the byte a selection started at and of the one it extends to.
+    selection: Option<(usize, usize)>,
+    /// Where the dump was drawn last, for mouse clicks.
+    inner: Rect,
+}
+
+/// The scroll position and selection, as saved with a workspace.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedSelection {
+    scroll: usize,
+    selection: Option<(usize, usize)>,
+}
+
+impl HexView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn line_count(&self) -> usize {
+        self.buffer.len().div_ceil(BYTES_PER_LINE)
+    }
+
+    /// The selected offsets, first to last.
+    fn selected(&self) -> Option<RangeInclusive<usize>> {
+        let (anchor, cursor) = self.selection?;
+        Some(anchor.min(cursor)..=anchor.max(cursor))
+    }
+
+    /// Tell the other components which bytes are selected.
+    fn send_selection(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            let selected = self.selected().map(|s| *s.start()..s.end() + 1);
+            tx.send(Action::SetSelection(selected))?;
+        }
+        Ok(())
+    }
+
+    /// The end of the selection, the first byte of the top line without one.
+    fn cursor(&self) -> usize {
+        match self.selection {
+            Some((_, cursor)) => cursor,
+            None => self.scroll * BYTES_PER_LINE,
+        }
+    }
+
+    /// Offset of the byte drawn at `position`, in the hex digits or in the ASCII gutter. With
+    /// `clamp`, positions beside or past the bytes give the closest one.
+    fn offset_at(&self, position: Position, clamp: bool) -> Option<usize> {
+        if self.buffer.is_empty() || (!clamp && !self.inner.contains(position)) {
+            return None;
+        }
+        let column = position.x.saturating_sub(self.inner.x);
+        let index = if column >= ASCII_COLUMN {
+            column - ASCII_COLUMN
+        } else if column >= HEX_COLUMN {
+            (column - HEX_COLUMN) / 3
+        } else if clamp {
+            0
+        } else {
+            return None;
+        };
+        if !clamp && usize::from(index) >= BYTES_PER_LINE {
+            return None;
+        }
+        let index = usize::from(index).min(BYTES_PER_LINE - 1);
+        let row = usize::from(position.y.saturating_sub(self.inner.y)) + self.scroll;
+        let offset = row * BYTES_PER_LINE + index;
+        if clamp {
+            Some(offset.min(self.buffer.len() - 1))
+        } else {
+            (offset < self.buffer.len()).then_some(offset)
+        }
+    }
+}
+
+/// Columns of the first hex digit and of the first character of the ASCII gutter in a line of
+/// the dump.
+const HEX_COLUMN: u16 = 10;
+const ASCII_COLUMN: u16 = HEX_COLUMN + BYTES_PER_LINE as u16 * 3 + 2;
+
+/// Format one line of the dump, padded so that the gutters of all lines align. `style` gives the
+/// style of the byte at an offset.
+fn dump_line(offset: usize, chunk: &[u8], style: impl Fn(usize, u8) -> Style) -> Line<'static> {
+    let mut spans = vec![Span::raw(format!("{offset:08x}  "))];
+    for (i, b) in chunk.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(format!("{b:02x}"), style(offset + i, *b)));
+    }
+    let padding = (BYTES_PER_LINE - chunk.len()) * 3;
+    spans.push(Span::raw(format!("{:padding$}  |", "")));
+    for (i, b) in chunk.iter().enumerate() {
+        let c = if b.is_ascii_graphic() || *b == b' ' {
+            char::from(*b)
+        } else {
+            '.'
+        };
+        spans.push(Span::styled(c.to_string(), style(offset + i, *b)));
+    }
+    spans.push(Span::raw("|"));
+    Line::from(spans)
+}
+
+impl Component for HexView {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn panel(&self) -> Option<Panel> {
+        Some(Panel::HexView)
+    }
+
+    fn selection(&self) -> Option<Value> {
+        let saved = SavedSelection {
+            scroll: self.scroll,
+            selection: self.selection,
+        };
+        serde_json::to_value(saved).ok()
+    }
+
+    /// Restore the scroll position and selection, as far as they lie within the buffer.
+    fn select(&mut self, selection: Value) -> Result<()> {
+        let saved: SavedSelection = serde_json::from_value(selection)?;
+        let last = self.buffer.len().checked_sub(1);
+        self.scroll = saved.scroll.min(self.line_count().saturating_sub(1));
+        self.selection = saved
+            .selection
+            .zip(last)
+            .map(|((anchor, cursor), last)| (anchor.min(last), cursor.min(last)));
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::SetCursor(self.cursor()))?;
+        }
+        self.send_selection()
+    }
+
+    /// Pressing the left button on a byte starts a selection, dragging extends it.
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.selection = self
+                    .offset_at(position, false)
+                    .map(|offset| (offset, offset));
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let (Some((anchor, _)), Some(offset)) =
+                    (self.selection, self.offset_at(position, true))
+                {
+                    self.selection = Some((anchor, offset));
+                }
+            }
+            _ => return Ok(None),
+        }
+        self.send_selection()?;
+        Ok(Some(Action::SetCursor(self.cursor())))
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetBuffer(bytes) => {
+                self.buffer = bytes;
+                self.selection = None;
+                self.scroll = self.scroll.min(self.line_count().saturating_sub(1));
+                return Ok(Some(Action::SetCursor(self.cursor())));
+            }
+            Action::ScrollDown => {
+                self.scroll = (self.scroll + 1).min(self.line_count().saturating_sub(1));
+                return Ok(Some(Action::SetCursor(self.cursor())));
+            }
+            Action::ScrollUp => {
+                self.scroll = self.scroll.saturating_sub(1);
+                return Ok(Some(Action::SetCursor(self.cursor())));
+            }
+            Action::GoTo(offset) => {
+                self.scroll = (offset / BYTES_PER_LINE).min(self.line_count().saturating_sub(1));
+                return Ok(Some(Action::SetCursor(self.cursor())));
+            }
+            Action::AddBookmark => {
+                let offset = self.scroll * BYTES_PER_LINE;
+                return Ok(Some(Action::Bookmarked(Bookmark {
+                    name: format!("{offset:#010x}"),
+                    offset,
+                })));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let invalid = self.config.theme.style(Role::InvalidByte);
+        let selected_style = self.config.theme.style(Role::Selected);
+        let selected = self.selected();
+        let style = |offset, byte: u8| {
+            if selected.as_ref().is_some_and(|s| s.contains(&offset)) {
+                selected_style
+            } else if byte.is_ascii_graphic() || byte == b' ' {
+                Style::new()
+            } else {
+                invalid
+            }
+        };
+        let lines: Vec<Line> = self
+            .buffer
+            .chunks(BYTES_PER_LINE)
+            .enumerate()
+            .skip(self.scroll)
+            .take(usize::from(area.height))
+            .map(|(i, chunk)| dump_line(i * BYTES_PER_LINE, chunk, style))
+            .collect();
+        let mut title = format!("Hex view ({} bytes", self.buffer.len());
+        if let Some(selected) = self.selected() {
+            let count = selected.end() - selected.start() + 1;
+            title.push_str(&format!(", {count} selected at {:#x}", selected.start()));
+        }
+        title.push(')');
+        let block = Block::default().borders(Borders::ALL).title(title);
+        self.inner = block.inner(area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::import;
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: crossterm::event::KeyModifiers::empty(),
+        }
+    }
+
+    #[test]
+    fn test_drag_selects_bytes() -> Result<()> {
+        let mut hex_view = HexView::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        hex_view.register_action_handler(tx)?;
+        hex_view.update(Action::SetBuffer((0..40).collect()))?;
+        hex_view.inner = Rect::new(1, 1, 78, 10);
+        let left = MouseButton::Left;
+        // The second byte of the first line.
+        hex_view.handle_mouse_event(mouse(MouseEventKind::Down(left), 1 + 13, 1))?;
+        assert_eq!(hex_view.selected(), Some(1..=1));
+        // The ASCII gutter of the second line.
+        let action = hex_view.handle_mouse_event(mouse(MouseEventKind::Drag(left), 1 + 61, 2))?;
+        assert_eq!(hex_view.selected(), Some(1..=17));
+        assert_eq!(action, Some(Action::SetCursor(17)));
+        let mut sent = Vec::new();
+        while let Ok(action) = rx.try_recv() {
+            sent.push(action);
+        }
+        assert_eq!(
+            sent,
+            [
+                Action::SetSelection(Some(1..2)),
+                Action::SetSelection(Some(1..18))
+            ]
+        );
+        // Past the last byte.
+        hex_view.handle_mouse_event(mouse(MouseEventKind::Drag(left), 1 + 70, 8))?;
+        assert_eq!(hex_view.selected(), Some(1..=39));
+        // Outside the bytes.
+        hex_view.handle_mouse_event(mouse(MouseEventKind::Down(left), 1 + 70, 8))?;
+        assert_eq!(hex_view.selected(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_selection_is_restored_within_the_buffer() -> Result<()> {
+        let mut hex_view = HexView::new();
+        hex_view.update(Action::SetBuffer((0..40).collect()))?;
+        hex_view.update(Action::ScrollDown)?;
+        hex_view.selection = Some((20, 35));
+        let saved = hex_view.selection().unwrap();
+
+        let mut restored = HexView::new();
+        restored.update(Action::SetBuffer((0..40).collect()))?;
+        restored.select(saved.clone())?;
+        assert_eq!((restored.scroll, restored.selected()), (1, Some(20..=35)));
+        // A shorter buffer than the one the selection was saved with.
+        restored.update(Action::SetBuffer((0..10).collect()))?;
+        restored.select(saved)?;
+        assert_eq!((restored.scroll, restored.selected()), (0, Some(9..=9)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dump_line_round_trips_through_import() {
+        let bytes: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(7)).collect();
+        let dump: Vec<String> = bytes
+            .chunks(BYTES_PER_LINE)
+            .enumerate()
+            .map(|(i, chunk)| dump_line(i * BYTES_PER_LINE, chunk, |_, _| Style::new()).to_string())
+            .collect();
+        assert_eq!(import::parse(&dump.join("\n")), Ok(bytes));
+    }
+}
@@ -1,12 +1,19 @@
 use color_eyre::Result;
-use ratatui::prelude::*;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{prelude::*, widgets::*};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::{Display, EnumIter, IntoEnumIterator};
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::component::Component;
+use crate::components::tab_at;
+use crate::interpret::{Encoding, Endianness, Kind};
+use crate::layout::Panel;
+use crate::theme::Role;
 use crate::{action::Action, config::Config};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize, Default)]
 enum ActiveHeaderElement {
     Kind,
     Endianness,
@@ -22,12 +29,73 @@ pub struct Home {
 
     active_header_element: ActiveHeaderElement,
     input: String,
+    kind: Kind,
+    endianness: Endianness,
+    encoding: Encoding,
+    /// Where the header was drawn last, for mouse clicks.
+    header_area: Rect,
+}
+
+/// The choice after (or before) `value`, wrapping around.
+fn cycle<T: IntoEnumIterator + PartialEq>(value: &T, forward: bool) -> T {
+    let mut choices: Vec<T> = T::iter().collect();
+    let len = choices.len();
+    let i = choices.iter().position(|c| c == value).unwrap_or(0);
+    let next = if forward {
+        (i + 1) % len
+    } else {
+        (i + len - 1) % len
+    };
+    choices.swap_remove(next)
 }
 
 impl Home {
     pub fn new() -> Self {
         Self::default()
     }
+
+    fn buffer(&self) -> Vec<u8> {
+        parse_hex(&self.input)
+    }
+
+    fn header_title(&self, element: ActiveHeaderElement) -> String {
+        match element {
+            ActiveHeaderElement::Kind => format!("{element}: {}", self.kind),
+            ActiveHeaderElement::Endianness => format!("{element}: {}", self.endianness),
+            ActiveHeaderElement::Encoding => format!("{element}: {}", self.encoding),
+            ActiveHeaderElement::Input => element.to_string(),
+        }
+    }
+
+    /// The action selecting the next (or previous) choice of the active header element.
+    fn cycle_active(&self, forward: bool) -> Option<Action> {
+        match self.active_header_element {
+            ActiveHeaderElement::Kind => Some(Action::SetKind(cycle(&self.kind, forward))),
+            ActiveHeaderElement::Endianness => {
+                Some(Action::SetEndianness(cycle(&self.endianness, forward)))
+            }
+            ActiveHeaderElement::Encoding => {
+                Some(Action::SetEncoding(cycle(&self.encoding, forward)))
+            }
+            ActiveHeaderElement::Input => None,
+        }
+    }
+}
+
+/// Interpret `input` as hex digits, an odd digit count is padded with a leading zero.
+pub fn parse_hex(input: &str) -> Vec<u8> {
+    let digits: Vec<u8> = input
+        .chars()
+        .filter_map(|c| c.to_digit(16))
+        .map(|d| d as u8)
+        .collect();
+    let padding = digits.len() % 2;
+    let mut bytes = vec![0u8; digits.len().div_ceil(2)];
+    for (i, digit) in digits.into_iter().enumerate() {
+        let i = i + padding;
+        bytes[i / 2] |= digit << if i.is_multiple_of(2) { 4 } else { 0 };
+    }
+    bytes
 }
 
 impl Component for Home {
@@ -41,42 +109,186 @@ impl Component for Home {
         Ok(())
     }
 
+    fn panel(&self) -> Option<Panel> {
+        Some(Panel::Input)
+    }
+
+    /// The active header element.
+    fn selection(&self) -> Option<Value> {
+        serde_json::to_value(self.active_header_element).ok()
+    }
+
+    fn select(&mut self, selection: Value) -> Result<()> {
+        self.active_header_element = serde_json::from_value(selection)?;
+        Ok(())
+    }
+
+    fn constraint(&self) -> Constraint {
+        Constraint::Length(4)
+    }
+
+    /// Clicking a header tab selects the next choice, a right click the previous one.
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        let forward = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => true,
+            MouseEventKind::Down(MouseButton::Right) => false,
+            _ => return Ok(None),
+        };
+        if !self
+            .header_area
+            .contains(Position::new(mouse.column, mouse.row))
+        {
+            return Ok(None);
+        }
+        let titles: Vec<String> = ActiveHeaderElement::iter()
+            .map(|e| self.header_title(e))
+            .collect();
+        let Some(element) = tab_at(&titles, mouse.column - self.header_area.x)
+            .and_then(|i| ActiveHeaderElement::iter().nth(i))
+        else {
+            return Ok(None);
+        };
+        self.active_header_element = element;
+        Ok(self.cycle_active(forward))
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
-            Action::Tick => todo!(),
-            Action::Render => todo!(),
-            Action::Resize(_, _) => todo!(),
-            Action::Suspend => todo!(),
-            Action::Resume => todo!(),
-            Action::Quit => todo!(),
-            Action::ClearScreen => todo!(),
-            Action::Error(_) => todo!(),
-            Action::Help => todo!(),
-            Action::ScrollDown => todo!(),
-            Action::ScrollUp => todo!(),
-            Action::SelectRight => todo!(),
-            Action::SelectLeft => todo!(),
-            Action::CharacterInput(character_input) => todo!(),
-            _ => todo!(),
+            Action::CharacterInput(character_input) => {
+                let Some(digit) = character_input.digit() else {
+                    return Ok(None);
+                };
+                self.input
+                    .push(char::from_digit(digit.into(), 16).unwrap_or('0'));
+                Ok(Some(Action::SetBuffer(self.buffer())))
+            }
+            Action::DeleteCharacter => {
+                self.input.pop();
+                Ok(Some(Action::SetBuffer(self.buffer())))
+            }
+            Action::SetBuffer(bytes) if bytes != self.buffer() => {
+                self.input = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                Ok(None)
+            }
+            // Up and down move between the header elements, left and right change the choice.
+            Action::ScrollDown => {
+                self.active_header_element = cycle(&self.active_header_element, true);
+                Ok(None)
+            }
+            Action::ScrollUp => {
+                self.active_header_element = cycle(&self.active_header_element, false);
+                Ok(None)
+            }
+            Action::SelectRight => Ok(self.cycle_active(true)),
+            Action::SelectLeft => Ok(self.cycle_active(false)),
+            Action::SetKind(kind) => {
+                self.kind = kind;
+                Ok(None)
+            }
+            Action::SetEndianness(endianness) => {
+                self.endianness = endianness;
+                Ok(None)
+            }
+            Action::SetEncoding(encoding) => {
+                self.encoding = encoding;
+                Ok(None)
+            }
+            _ => Ok(None),
         }
-
-        // match action {
-        //     Action::Tick => {
-        //         tracing::info!("Home: Tick");
-        //         // trace_dbg!("Home: Tick");
-        //         // add any logic here that should run on every tick
-        //     }
-        //     Action::Render => {
-        //         // add any logic here that should run on every render
-        //     }
-        //     _ => {}
-        // }
-        Ok(None)
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        // frame.render_widget(Block::default().borders(Borders::ALL).title("Home"), area);
-        // Paragraph::new("hello world"), area);
+        let block = Block::default().borders(Borders::ALL).title("Input (hex)");
+        let [header, input] = Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+            .areas(block.inner(area));
+        self.header_area = header;
+        frame.render_widget(block, area);
+        let titles = ActiveHeaderElement::iter().map(|e| self.header_title(e));
+        let selected = ActiveHeaderElement::iter().position(|e| e == self.active_header_element);
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(selected)
+                .highlight_style(self.config.theme.style(Role::HeaderActive)),
+            header,
+        );
+        frame.render_widget(Paragraph::new(format!("{}_", self.input)), input);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn click(home: &mut Home, column: u16, button: MouseButton) -> Result<Option<Action>> {
+        home.handle_mouse_event(MouseEvent {
+            kind: MouseEventKind::Down(button),
+            column,
+            row: 1,
+            modifiers: crossterm::event::KeyModifiers::empty(),
+        })
+    }
+
+    #[test]
+    fn test_click_header_tabs() -> Result<()> {
+        let mut home = Home::new();
+        home.header_area = Rect::new(1, 1, 80, 1);
+        // " Kind: u8 │ Endianness: Little │ Encoding: UTF-8 │ Input"
+        assert_eq!(
+            click(&mut home, 3, MouseButton::Left)?,
+            Some(Action::SetKind(cycle(&Kind::default(), true)))
+        );
+        assert_eq!(
+            click(&mut home, 3, MouseButton::Right)?,
+            Some(Action::SetKind(Kind::iter().next_back().unwrap()))
+        );
+        assert_eq!(
+            click(&mut home, 15, MouseButton::Left)?,
+            Some(Action::SetEndianness(Endianness::Big))
+        );
+        assert_eq!(click(&mut home, 11, MouseButton::Left)?, None);
+        assert_eq!(click(&mut home, 55, MouseButton::Left)?, None);
+        assert_eq!(home.active_header_element, ActiveHeaderElement::Input);
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_cycle_the_active_header_element() -> Result<()> {
+        let mut home = Home::new();
+        assert_eq!(home.update(Action::SelectRight)?, None);
+        home.update(Action::ScrollDown)?;
+        assert_eq!(home.active_header_element, ActiveHeaderElement::Kind);
+        assert_eq!(
+            home.update(Action::SelectRight)?,
+            Some(Action::SetKind(cycle(&Kind::default(), true)))
+        );
+        home.update(Action::ScrollDown)?;
+        home.update(Action::ScrollDown)?;
+        assert_eq!(
+            home.update(Action::SelectLeft)?,
+            Some(Action::SetEncoding(cycle(&Encoding::default(), false)))
+        );
+        home.update(Action::ScrollUp)?;
+        assert_eq!(home.active_header_element, ActiveHeaderElement::Endianness);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_hex_input_round_trip(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+            let mut home = Home::new();
+            home.update(Action::SetBuffer(bytes.clone())).unwrap();
+            prop_assert_eq!(parse_hex(&home.input), bytes);
+        }
+
+        #[test]
+        fn test_hex_input_formats_back(input in "([0-9a-f]{2})*") {
+            let mut home = Home::new();
+            home.update(Action::SetBuffer(parse_hex(&input))).unwrap();
+            prop_assert_eq!(home.input, input);
+        }
+    }
+}
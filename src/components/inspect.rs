@@ -0,0 +1,111 @@
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::component::Component;
+use crate::interpret::{Encoding, Endianness, Kind};
+use crate::layout::Panel;
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+/// Table of every numeric and text interpretation of the current buffer.
+#[derive(Default)]
+pub struct Inspect {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    buffer: Vec<u8>,
+    kind: Kind,
+    endianness: Endianness,
+    encoding: Encoding,
+}
+
+impl Inspect {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Replace control characters so that decoded text cannot break the layout.
+pub fn printable(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { '·' } else { c })
+        .collect()
+}
+
+impl Component for Inspect {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn panel(&self) -> Option<Panel> {
+        Some(Panel::Inspect)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetBuffer(bytes) => self.buffer = bytes,
+            Action::SetKind(kind) => self.kind = kind,
+            Action::SetEndianness(endianness) => self.endianness = endianness,
+            Action::SetEncoding(encoding) => self.encoding = encoding,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let decode = |kind: Kind, endianness| {
+            kind.decode(&self.buffer, endianness)
+                .unwrap_or_else(|| "-".to_string())
+        };
+        // The selected kind, endianness and encoding are highlighted.
+        let selected = self.config.theme.style(Role::Selected);
+        let numbers = Kind::iter().map(|kind| {
+            let cells = Endianness::iter().map(|endianness| {
+                let cell = Cell::new(decode(kind, endianness));
+                if kind == self.kind && endianness == self.endianness {
+                    cell.style(selected)
+                } else {
+                    cell
+                }
+            });
+            let name = Cell::new(kind.to_string());
+            Row::new([name].into_iter().chain(cells))
+        });
+        let text = Encoding::iter().map(|encoding| {
+            let decoded = Cell::new(printable(&encoding.decode(&self.buffer)));
+            Row::new([
+                Cell::new(encoding.to_string()),
+                if encoding == self.encoding {
+                    decoded.style(selected)
+                } else {
+                    decoded
+                },
+            ])
+        });
+        let header = Row::new(["", "Little endian", "Big endian"]).bold();
+        let table = Table::new(
+            numbers.chain([Row::new([""; 3])]).chain(text),
+            [
+                Constraint::Length(10),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Inspect ({} bytes)", self.buffer.len())),
+        );
+        frame.render_widget(table, area);
+        Ok(())
+    }
+}
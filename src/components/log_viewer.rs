@@ -0,0 +1,191 @@
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::Level;
+
+use crate::component::Component;
+use crate::components::popup_area;
+use crate::logging::{LOG_BUFFER, LogBuffer};
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+/// Levels the records can be filtered by, most severe first.
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+/// Popup listing the latest log records of the session, newest at the bottom.
+///
+/// Left and right change the least severe level shown, scrolling up stops following new records
+/// until scrolled back down.
+pub struct LogViewer {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    buffer: LogBuffer,
+    visible: bool,
+    level: Level,
+    /// Records hidden below the bottom of the list, `0` follows new records.
+    scroll: usize,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self::with_buffer(LOG_BUFFER.clone())
+    }
+}
+
+impl LogViewer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_buffer(buffer: LogBuffer) -> Self {
+        Self {
+            command_tx: None,
+            config: Config::default(),
+            buffer,
+            visible: false,
+            level: Level::DEBUG,
+            scroll: 0,
+        }
+    }
+
+    fn cycle_level(&mut self, forward: bool) {
+        let position = LEVELS.iter().position(|level| *level == self.level);
+        let position = position.unwrap_or(0);
+        let next = if forward {
+            (position + 1).min(LEVELS.len() - 1)
+        } else {
+            position.saturating_sub(1)
+        };
+        self.level = LEVELS[next];
+        self.scroll = 0;
+    }
+}
+
+impl Component for LogViewer {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn captures_focus(&self) -> bool {
+        self.visible
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::ToggleLogs => {
+                self.visible = !self.visible;
+                self.scroll = 0;
+            }
+            Action::SelectRight => self.cycle_level(true),
+            Action::SelectLeft => self.cycle_level(false),
+            Action::ScrollUp => self.scroll += 1,
+            Action::ScrollDown => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let area = popup_area(area, 90, 80);
+        frame.render_widget(Clear, area);
+
+        let records = self.buffer.records(self.level);
+        let height = area.height.saturating_sub(2) as usize;
+        self.scroll = self.scroll.min(records.len().saturating_sub(height));
+        let end = records.len() - self.scroll;
+        let error_style = self.config.theme.style(Role::Error);
+        let lines: Vec<Line> = records[end.saturating_sub(height)..end]
+            .iter()
+            .map(|record| {
+                let level = match record.level {
+                    Level::ERROR => Span::styled(format!("{:<5}", record.level), error_style),
+                    Level::WARN => Span::raw(format!("{:<5}", record.level)).bold(),
+                    level => Span::raw(format!("{level:<5}")),
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:>9.3} ", record.seconds)).dim(),
+                    level,
+                    Span::raw(format!(" {}: {}", record.target, record.message)),
+                ])
+            })
+            .collect();
+        let title = format!(
+            "Logs (◂ {} ▸, {} records{})",
+            self.level,
+            records.len(),
+            if self.scroll > 0 { ", scrolled" } else { "" },
+        );
+        let block = Block::default().borders(Borders::ALL).title(title);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::{Terminal, backend::TestBackend};
+    use tracing::{debug, error, info};
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+
+    fn render(log_viewer: &mut LogViewer) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(120, 10)).unwrap();
+        terminal
+            .draw(|frame| log_viewer.draw(frame, frame.area()).unwrap())
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..10)
+            .map(|y| (0..120).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_filters_and_scrolls_records() -> Result<()> {
+        let buffer = LogBuffer::new(100);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            for i in 0..10 {
+                debug!("key {i}");
+            }
+            info!("reloaded");
+            error!("broken binding");
+        });
+        let mut log_viewer = LogViewer::with_buffer(buffer);
+        log_viewer.update(Action::ToggleLogs)?;
+        let screen = render(&mut log_viewer);
+        // Following the newest records, 6 rows fit the popup.
+        assert!(screen[1].contains("Logs (◂ DEBUG ▸, 12 records)"));
+        assert!(screen[7].contains("ERROR byte_me::components::log_viewer"));
+        assert!(screen[2].contains("key 6"));
+
+        log_viewer.update(Action::ScrollUp)?;
+        assert!(render(&mut log_viewer)[2].contains("key 5"));
+
+        log_viewer.update(Action::SelectLeft)?;
+        let screen = render(&mut log_viewer);
+        assert!(screen[1].contains("Logs (◂ INFO ▸, 2 records)"));
+        assert!(screen[2].contains("INFO  byte_me::components::log_viewer::tests: reloaded"));
+
+        log_viewer.update(Action::ToggleLogs)?;
+        assert_eq!(render(&mut log_viewer)[1].trim(), "");
+        Ok(())
+    }
+}
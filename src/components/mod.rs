@@ -1,12 +1,68 @@
-use ratatui::layout::Constraint;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::text::Line;
 
-use crate::component::Component;
-
-pub mod fps;
+pub mod bit_grid;
+pub mod bits;
+pub mod bitwise;
+pub mod checksums;
+pub mod compare;
+pub mod export;
+pub mod help;
+pub mod hex_view;
 pub mod home;
-pub mod home_old;
-pub mod sized_component;
+pub mod inspect;
+pub mod log_viewer;
+pub mod palette;
+pub mod restore_prompt;
+pub mod status_bar;
+pub mod which_key;
+
+/// Centered area covering the given percentage of `area`, used for popups.
+pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Percentage(percent_y)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(percent_x)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// Index of the tab at `column`, relative to the left of a [`ratatui::widgets::Tabs`] drawn with
+/// the default padding and divider. `None` on a divider or behind the last tab.
+pub fn tab_at<T: AsRef<str>>(titles: &[T], column: u16) -> Option<usize> {
+    let mut start = 0;
+    for (i, title) in titles.iter().enumerate() {
+        // A space of padding on both sides.
+        let end = start + Line::raw(title.as_ref()).width() as u16 + 2;
+        if (start..end).contains(&column) {
+            return Some(i);
+        }
+        start = end + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::{buffer::Buffer, widgets::Tabs, widgets::Widget};
+
+    use super::*;
 
-trait ConstrainedComponent: Component {
-    fn constraint(&self) -> Constraint;
+    #[test]
+    fn test_tab_at_matches_rendered_tabs() {
+        let titles = ["Kind: u8", "Endianness: Little", "Input"];
+        let area = Rect::new(0, 0, 40, 1);
+        let mut buffer = Buffer::empty(area);
+        Tabs::new(titles).render(area, &mut buffer);
+        for (column, tab) in [(0, Some(0)), (9, Some(0)), (11, Some(1)), (38, Some(2))] {
+            assert_eq!(tab_at(&titles, column), tab);
+        }
+        for column in [10, 31] {
+            assert_eq!(buffer[(column, 0)].symbol(), "│");
+            assert_eq!(tab_at(&titles, column), None);
+        }
+        assert_eq!(tab_at(&titles, 39), None);
+    }
 }
@@ -0,0 +1,354 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use ratatui::{prelude::*, widgets::*};
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::action::{Bookmark, CharacterInput};
+use crate::app::Mode;
+use crate::component::Component;
+use crate::components::popup_area;
+use crate::interpret::{Encoding, Endianness, Kind};
+use crate::theme::{Role, Theme};
+use crate::workspace;
+use crate::{action::Action, config::Config};
+
+/// An action the palette can run.
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    title: String,
+    description: String,
+    action: Action,
+}
+
+impl Entry {
+    fn new(title: String, action: Action) -> Self {
+        Self {
+            title,
+            description: action.description(),
+            action,
+        }
+    }
+}
+
+/// Popup to search and run every action, type choice and bookmark by fuzzy matching.
+#[derive(Default)]
+pub struct CommandPalette {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    visible: bool,
+    query: String,
+    selected: usize,
+    bookmarks: Vec<Bookmark>,
+    /// Names of the saved workspaces, read when the palette opens.
+    workspaces: Vec<String>,
+    matcher: SkimMatcherV2,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every runnable entry, actions with an argument are listed once per choice.
+    fn entries(&self) -> Vec<Entry> {
+        Action::iter()
+            .flat_map(|action| -> Vec<Entry> {
+                let with = |argument: String, action: Action| {
+                    Entry::new(format!("{action} {argument}"), action)
+                };
+                match action {
+                    Action::SwitchMode(_) => Mode::iter()
+                        .map(|mode| with(mode.to_string(), Action::SwitchMode(mode)))
+                        .collect(),
+                    Action::CharacterInput(_) => CharacterInput::iter()
+                        .map(|input| with(input.to_string(), Action::CharacterInput(input)))
+                        .collect(),
+                    Action::SetKind(_) => Kind::iter()
+                        .map(|kind| with(kind.to_string(), Action::SetKind(kind)))
+                        .collect(),
+                    Action::SetEndianness(_) => Endianness::iter()
+                        .map(|endianness| {
+                            with(endianness.to_string(), Action::SetEndianness(endianness))
+                        })
+                        .collect(),
+                    Action::SetEncoding(_) => Encoding::iter()
+                        .map(|encoding| with(encoding.to_string(), Action::SetEncoding(encoding)))
+                        .collect(),
+                    Action::SetTheme(_) => Theme::names()
+                        .map(|name| with(name.to_string(), Action::SetTheme(name.to_string())))
+                        .collect(),
+                    Action::GoTo(_) => self
+                        .bookmarks
+                        .iter()
+                        .map(|bookmark| {
+                            let action = Action::GoTo(bookmark.offset);
+                            Entry::new(format!("Bookmark {}", bookmark.name), action)
+                        })
+                        .collect(),
+                    Action::LoadWorkspace(_) => self
+                        .workspaces
+                        .iter()
+                        .map(|name| with(name.clone(), Action::LoadWorkspace(name.clone())))
+                        .collect(),
+                    // Named as typed, see `command`.
+                    Action::SaveWorkspace(_) | Action::CompareWith(_) => Vec::new(),
+                    // Internal actions and the palette itself.
+                    Action::Tick
+                    | Action::Render
+                    | Action::Resize(..)
+                    | Action::Resume
+                    | Action::Error(_)
+                    | Action::Info(_)
+                    | Action::SetBuffer(_)
+                    | Action::SetCompareBuffer(..)
+                    | Action::Bookmarked(_)
+                    | Action::SetBookmarks(_)
+                    | Action::SetCursor(_)
+                    | Action::SetSelection(_)
+                    | Action::PendingKeys(_)
+                    | Action::OfferRestore(_)
+                    | Action::RestoreSession(_)
+                    | Action::RestoreSelections(_)
+                    | Action::CommandPalette => Vec::new(),
+                    action => vec![Entry::new(action.to_string(), action)],
+                }
+            })
+            .collect()
+    }
+
+    /// The command typed as the query, `save NAME`, `load NAME` or `compare FILE`.
+    fn command(&self) -> Option<Entry> {
+        let (command, name) = self.query.trim().split_once(' ')?;
+        let name = name.trim().to_string();
+        let action = match command {
+            "save" => Action::SaveWorkspace(name),
+            "load" => Action::LoadWorkspace(name),
+            "compare" => Action::CompareWith(name.into()),
+            _ => return None,
+        };
+        Some(Entry::new(self.query.trim().to_string(), action))
+    }
+
+    /// Entries matching the query, best match first. A typed command comes before them.
+    fn matches(&self) -> Vec<Entry> {
+        let mut scored: Vec<(i64, Entry)> = self
+            .entries()
+            .into_iter()
+            .filter_map(|entry| {
+                let text = format!("{} {}", entry.title, entry.description);
+                let score = self.matcher.fuzzy_match(&text, &self.query)?;
+                Some((score, entry))
+            })
+            .collect();
+        // Stable, so entries with the same score keep their order.
+        scored.sort_by_key(|(score, _)| -score);
+        self.command()
+            .into_iter()
+            .chain(scored.into_iter().map(|(_, entry)| entry))
+            .collect()
+    }
+
+    fn close(&mut self) {
+        self.visible = false;
+        self.query.clear();
+        self.selected = 0;
+    }
+}
+
+impl Component for CommandPalette {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn captures_focus(&self) -> bool {
+        self.visible
+    }
+
+    fn captures_keys(&self) -> bool {
+        self.visible
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('p') if control => self.close(),
+            KeyCode::Enter => {
+                let action = self.matches().into_iter().nth(self.selected);
+                self.close();
+                return Ok(action.map(|entry| entry.action));
+            }
+            KeyCode::Down | KeyCode::Tab => self.selected = self.selected.saturating_add(1),
+            KeyCode::Up | KeyCode::BackTab => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) if !control => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::CommandPalette if self.visible => self.close(),
+            Action::CommandPalette => {
+                self.visible = true;
+                self.workspaces = workspace::names(&self.config.config.data_dir);
+            }
+            Action::Bookmarked(bookmark) => {
+                self.bookmarks.retain(|b| b.offset != bookmark.offset);
+                self.bookmarks.push(bookmark);
+            }
+            Action::SetBookmarks(bookmarks) => self.bookmarks = bookmarks,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let area = popup_area(area, 70, 60);
+        frame.render_widget(Clear, area);
+        let block = Block::default().borders(Borders::ALL).title("Commands");
+        let [input, list] =
+            Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(block.inner(area));
+        frame.render_widget(block, area);
+        frame.render_widget(
+            Paragraph::new(format!(": {}_", self.query))
+                .block(Block::default().borders(Borders::BOTTOM)),
+            input,
+        );
+
+        let matches = self.matches();
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+        let rows = matches
+            .into_iter()
+            .map(|entry| Row::new([entry.title, entry.description]));
+        let table = Table::new(rows, [Constraint::Percentage(40), Constraint::Fill(1)])
+            .row_highlight_style(self.config.theme.style(Role::Selected));
+        let mut state = TableState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(table, list, &mut state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn type_query(palette: &mut CommandPalette, query: &str) -> Result<()> {
+        for c in query.chars() {
+            palette.handle_key_event(KeyEvent::from(KeyCode::Char(c)))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_entries_cover_choices_and_skip_internal_actions() {
+        let palette = CommandPalette::new();
+        let actions: Vec<Action> = palette.entries().into_iter().map(|e| e.action).collect();
+        for kind in Kind::iter() {
+            assert!(actions.contains(&Action::SetKind(kind)));
+        }
+        for encoding in Encoding::iter() {
+            assert!(actions.contains(&Action::SetEncoding(encoding)));
+        }
+        assert!(actions.contains(&Action::Quit));
+        assert!(!actions.contains(&Action::Tick));
+        assert!(!actions.contains(&Action::CommandPalette));
+    }
+
+    #[test]
+    fn test_run_best_match() -> Result<()> {
+        let mut palette = CommandPalette::new();
+        palette.update(Action::CommandPalette)?;
+        type_query(&mut palette, "kind i16")?;
+        let action = palette.handle_key_event(KeyEvent::from(KeyCode::Enter))?;
+        assert_eq!(action, Some(Action::SetKind(Kind::I16)));
+        assert!(!palette.visible);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_workspace_commands() -> Result<()> {
+        let mut palette = CommandPalette::new();
+        palette.update(Action::CommandPalette)?;
+        type_query(&mut palette, "save firmware")?;
+        let action = palette.handle_key_event(KeyEvent::from(KeyCode::Enter))?;
+        assert_eq!(action, Some(Action::SaveWorkspace("firmware".to_string())));
+
+        palette.update(Action::CommandPalette)?;
+        type_query(&mut palette, "load shared/firmware.json")?;
+        let action = palette.handle_key_event(KeyEvent::from(KeyCode::Enter))?;
+        assert_eq!(
+            action,
+            Some(Action::LoadWorkspace("shared/firmware.json".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_saved_workspaces_are_listed_when_opened() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let save = |name: &str| {
+            let path = workspace::path(dir.path(), name);
+            workspace::save(&path, &workspace::Workspace::default())
+        };
+        let mut config = Config::default();
+        config.config.data_dir = dir.path().to_path_buf();
+        let mut palette = CommandPalette::new();
+        palette.register_config_handler(config)?;
+        let listed = |palette: &CommandPalette| -> Vec<Action> {
+            palette
+                .entries()
+                .into_iter()
+                .filter(|entry| matches!(entry.action, Action::LoadWorkspace(_)))
+                .map(|entry| entry.action)
+                .collect()
+        };
+
+        save("firmware")?;
+        palette.update(Action::CommandPalette)?;
+        save("bootloader")?;
+        assert_eq!(
+            listed(&palette),
+            [Action::LoadWorkspace("firmware".to_string())]
+        );
+        palette.update(Action::CommandPalette)?;
+        palette.update(Action::CommandPalette)?;
+        assert_eq!(listed(&palette).len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bookmarks_are_listed() -> Result<()> {
+        let mut palette = CommandPalette::new();
+        palette.update(Action::Bookmarked(Bookmark {
+            name: "header".to_string(),
+            offset: 0x40,
+        }))?;
+        palette.update(Action::CommandPalette)?;
+        type_query(&mut palette, "header")?;
+        let action = palette.handle_key_event(KeyEvent::from(KeyCode::Enter))?;
+        assert_eq!(action, Some(Action::GoTo(0x40)));
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::component::Component;
+use crate::components::popup_area;
+use crate::recovery::SessionState;
+use crate::{action::Action, config::Config};
+
+/// Popup offering to restore a session that ended in a crash, `y` or enter restores it and `n`
+/// or escape starts afresh.
+#[derive(Default)]
+pub struct RestorePrompt {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    /// The interrupted session, while the prompt is shown.
+    offered: Option<SessionState>,
+}
+
+impl RestorePrompt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Component for RestorePrompt {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn captures_focus(&self) -> bool {
+        self.offered.is_some()
+    }
+
+    fn captures_keys(&self) -> bool {
+        self.offered.is_some()
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                Ok(self.offered.take().map(Action::RestoreSession))
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.offered = None;
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::OfferRestore(state) = action {
+            self.offered = Some(state);
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(state) = &self.offered else {
+            return Ok(());
+        };
+        let area = popup_area(area, 60, 30);
+        frame.render_widget(Clear, area);
+        let lines = vec![
+            Line::raw("The last session did not end normally."),
+            Line::raw(state.summary()),
+            Line::default(),
+            Line::from(vec![
+                Span::raw("Restore it? "),
+                Span::raw("y").bold(),
+                Span::raw(" / "),
+                Span::raw("n").bold(),
+            ]),
+        ];
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Restore session");
+        frame.render_widget(
+            Paragraph::new(lines).block(block).wrap(Wrap { trim: true }),
+            area,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn press(prompt: &mut RestorePrompt, code: KeyCode) -> Option<Action> {
+        prompt
+            .handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_restores_or_dismisses_the_offer() {
+        let state = SessionState {
+            input: "ff".to_string(),
+            ..SessionState::default()
+        };
+        let mut prompt = RestorePrompt::new();
+        assert!(!prompt.captures_keys());
+
+        prompt.update(Action::OfferRestore(state.clone())).unwrap();
+        assert!(prompt.captures_keys());
+        assert_eq!(press(&mut prompt, KeyCode::Char('x')), None);
+        assert_eq!(
+            press(&mut prompt, KeyCode::Char('y')),
+            Some(Action::RestoreSession(state.clone()))
+        );
+        assert!(!prompt.captures_focus());
+
+        prompt.update(Action::OfferRestore(state)).unwrap();
+        assert_eq!(press(&mut prompt, KeyCode::Esc), None);
+        assert!(!prompt.captures_focus());
+    }
+}
@@ -0,0 +1,215 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::app::Mode;
+use crate::component::Component;
+use crate::config::key_sequence_to_string;
+use crate::theme::Role;
+use crate::{action::Action, config::Config};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct StatusBarConfig {
+    /// Milliseconds an info message stays visible, `0` keeps it until the next message.
+    #[serde(default = "StatusBarConfig::default_info_timeout")]
+    pub info_timeout: u64,
+    /// Milliseconds an error stays visible, `0` keeps it until the next message.
+    #[serde(default = "StatusBarConfig::default_error_timeout")]
+    pub error_timeout: u64,
+}
+
+impl StatusBarConfig {
+    fn default_info_timeout() -> u64 {
+        3000
+    }
+
+    fn default_error_timeout() -> u64 {
+        10000
+    }
+}
+
+impl Default for StatusBarConfig {
+    fn default() -> Self {
+        Self {
+            info_timeout: Self::default_info_timeout(),
+            error_timeout: Self::default_error_timeout(),
+        }
+    }
+}
+
+/// Single line at the bottom of the screen showing the active mode, the latest message, the keys
+/// typed so far of a multi-key binding and the position in the buffer.
+#[derive(Default)]
+pub struct StatusBar {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+
+    mode: Mode,
+    /// The latest message and when it was shown.
+    message: Option<(Message, Instant)>,
+    pending: Vec<KeyEvent>,
+    buffer_len: usize,
+    cursor: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Message {
+    Info(String),
+    Error(String),
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hide the message once its timeout passed at `now`.
+    fn expire(&mut self, now: Instant) {
+        let Some((message, shown)) = &self.message else {
+            return;
+        };
+        let timeout = match message {
+            Message::Info(_) => self.config.status_bar.info_timeout,
+            Message::Error(_) => self.config.status_bar.error_timeout,
+        };
+        if timeout > 0 && now.duration_since(*shown) >= Duration::from_millis(timeout) {
+            self.message = None;
+        }
+    }
+
+    fn position(&self) -> String {
+        if self.buffer_len == 0 {
+            return "empty".to_string();
+        }
+        format!("{:#x} / {} bytes", self.cursor, self.buffer_len)
+    }
+}
+
+impl Component for StatusBar {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick => self.expire(Instant::now()),
+            Action::Info(text) => self.message = Some((Message::Info(text), Instant::now())),
+            Action::Error(text) => self.message = Some((Message::Error(text), Instant::now())),
+            Action::PendingKeys(keys) => self.pending = keys,
+            Action::SwitchMode(mode) => self.mode = mode,
+            Action::SetBuffer(bytes) => {
+                self.buffer_len = bytes.len();
+                self.cursor = self.cursor.min(bytes.len().saturating_sub(1));
+            }
+            Action::SetCursor(offset) => self.cursor = offset,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let mode = Line::styled(
+            format!(" {} ", self.mode),
+            self.config.theme.style(Role::HeaderActive),
+        );
+        let message = match &self.message {
+            Some((Message::Info(text), _)) => Line::raw(text.as_str()),
+            Some((Message::Error(text), _)) => {
+                Line::styled(text.as_str(), self.config.theme.style(Role::Error))
+            }
+            None => Line::default(),
+        };
+        let pending = key_sequence_to_string(&self.pending);
+        let position = self.position();
+        let [mode_area, message_area, pending_area, position_area] = Layout::horizontal([
+            Constraint::Length(mode.width() as u16),
+            Constraint::Fill(1),
+            Constraint::Length(pending.chars().count() as u16),
+            Constraint::Length(position.chars().count() as u16),
+        ])
+        .spacing(1)
+        .areas(area);
+        frame.render_widget(mode, mode_area);
+        frame.render_widget(Paragraph::new(message), message_area);
+        frame.render_widget(Line::raw(pending).bold(), pending_area);
+        frame.render_widget(Line::raw(position), position_area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+
+    fn render(status_bar: &mut StatusBar) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 1)).unwrap();
+        terminal
+            .draw(|frame| status_bar.draw(frame, frame.area()).unwrap())
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..60).map(|x| buffer[(x, 0)].symbol()).collect()
+    }
+
+    #[test]
+    fn test_messages_expire() {
+        let mut status_bar = StatusBar::new();
+        status_bar.update(Action::Info("Saved".into())).unwrap();
+        let (_, shown) = status_bar.message.clone().unwrap();
+        status_bar.expire(shown + Duration::from_millis(2999));
+        assert!(status_bar.message.is_some());
+        status_bar.expire(shown + Duration::from_secs(3));
+        assert_eq!(status_bar.message, None);
+
+        status_bar.update(Action::Error("Failed".into())).unwrap();
+        let (_, shown) = status_bar.message.clone().unwrap();
+        status_bar.expire(shown + Duration::from_secs(3));
+        assert!(status_bar.message.is_some());
+        status_bar.expire(shown + Duration::from_secs(10));
+        assert_eq!(status_bar.message, None);
+    }
+
+    #[test]
+    fn test_zero_timeout_keeps_message() {
+        let mut status_bar = StatusBar::new();
+        status_bar.config.status_bar.error_timeout = 0;
+        status_bar.update(Action::Error("Failed".into())).unwrap();
+        let (_, shown) = status_bar.message.clone().unwrap();
+        status_bar.expire(shown + Duration::from_secs(3600));
+        assert!(status_bar.message.is_some());
+    }
+
+    #[test]
+    fn test_shows_mode_message_and_position() {
+        let mut status_bar = StatusBar::new();
+        let empty = render(&mut status_bar);
+        assert!(empty.starts_with(" Home "), "{empty}");
+        assert!(empty.ends_with(" empty"), "{empty}");
+        for action in [
+            Action::SwitchMode(Mode::HexView),
+            Action::SetBuffer(vec![0; 40]),
+            Action::SetCursor(0x21),
+            Action::Error("Failed to draw".into()),
+        ] {
+            status_bar.update(action).unwrap();
+        }
+        let shown = render(&mut status_bar);
+        assert!(shown.starts_with(" HexView  Failed to draw "), "{shown}");
+        assert!(shown.ends_with(" 0x21 / 40 bytes"), "{shown}");
+        // The cursor stays within a shorter buffer.
+        status_bar.update(Action::SetBuffer(vec![0; 8])).unwrap();
+        assert!(render(&mut status_bar).ends_with("0x7 / 8 bytes"));
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::app::Mode;
+use crate::component::Component;
+use crate::config::{key_event_to_string, key_sequence_to_string};
+use crate::keymap::{KeyTrie, keymaps};
+use crate::{action::Action, config::Config};
+
+/// Popup listing the keys that complete a pending multi-key binding.
+#[derive(Default)]
+pub struct WhichKey {
+    command_tx: Option<UnboundedSender<Action>>,
+    /// Bindings of every mode, rebuilt when the configuration changes.
+    keymaps: HashMap<Mode, KeyTrie>,
+
+    mode: Mode,
+    pending: Vec<KeyEvent>,
+}
+
+impl WhichKey {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The next keys of the pending chord as `(key, description)`, keys that start longer chords
+    /// are described by the number of bindings they lead to.
+    fn completions(&self) -> Vec<(String, String)> {
+        let Some(node) = self
+            .keymaps
+            .get(&self.mode)
+            .and_then(|keymap| keymap.get(&self.pending))
+        else {
+            return Vec::new();
+        };
+        let mut completions: Vec<(String, String)> = node
+            .children()
+            .map(|(key, child)| {
+                let description = match child.action() {
+                    Some(action) if child.children().next().is_none() => action.description(),
+                    _ => format!("+{} bindings", child.binding_count()),
+                };
+                (format!("<{}>", key_event_to_string(key)), description)
+            })
+            .collect();
+        completions.sort();
+        completions
+    }
+}
+
+impl Component for WhichKey {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.keymaps = keymaps(&config);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SwitchMode(mode) => self.mode = mode,
+            Action::PendingKeys(keys) => self.pending = keys,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let completions = self.completions();
+        let key_width = completions
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = completions
+            .iter()
+            .map(|(_, description)| key_width + 1 + description.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 2;
+        let popup = Rect {
+            width: (width as u16).min(area.width),
+            height: (completions.len() as u16 + 2).min(area.height),
+            ..area
+        };
+        let popup = Rect {
+            x: area.right() - popup.width,
+            y: area.bottom() - popup.height,
+            ..popup
+        };
+        frame.render_widget(Clear, popup);
+        let table = Table::new(
+            completions
+                .into_iter()
+                .map(|(key, description)| Row::new([key, description])),
+            [Constraint::Length(key_width as u16), Constraint::Fill(1)],
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(key_sequence_to_string(&self.pending)),
+        );
+        frame.render_widget(table, popup);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::config::parse_key_sequence;
+
+    #[test]
+    fn test_completions_of_pending_keys() -> Result<()> {
+        let mut which_key = WhichKey::new();
+        let mut config = Config::default();
+        let bindings = [
+            ("<space><c><h>", Action::Help),
+            ("<space><c><p>", Action::CommandPalette),
+            ("<space><q>", Action::Quit),
+        ];
+        config.keybindings.insert(
+            Mode::Home,
+            bindings
+                .into_iter()
+                .map(|(keys, action)| (parse_key_sequence(keys).unwrap(), action))
+                .collect(),
+        );
+        which_key.register_config_handler(config)?;
+        which_key.update(Action::PendingKeys(parse_key_sequence("<space>").unwrap()))?;
+        assert_eq!(
+            which_key.completions(),
+            [
+                ("<c>".to_string(), "+2 bindings".to_string()),
+                ("<q>".to_string(), "Quit the application".to_string()),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,17 +1,36 @@
 #![allow(dead_code)] // Remove this once you start using the code
 
-use std::{collections::HashMap, env, path::PathBuf};
+use std::{
+    collections::HashMap,
+    env, fmt,
+    path::{Path, PathBuf},
+};
 
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode, ModifierKeyCode,
+};
 use derive_deref::{Deref, DerefMut};
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
-use ratatui::style::{Color, Modifier, Style};
-use serde::{Deserialize, de::Deserializer};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use ratatui::style::Style;
+use serde::{
+    Deserialize,
+    de::{self, DeserializeOwned, Deserializer},
+};
+use strum::IntoEnumIterator;
 use tracing::error;
 
-use crate::{action::Action, app::Mode};
+use crate::{
+    action::Action,
+    app::Mode,
+    checksum::ChecksumConfig,
+    components::status_bar::StatusBarConfig,
+    export::ExportConfig,
+    layout::{LayoutNode, Layouts},
+    theme::{Theme, ThemeConfig, try_parse_style},
+};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
@@ -27,10 +46,30 @@ pub struct AppConfig {
 pub struct Config {
     #[serde(default, flatten)]
     pub config: AppConfig,
+    /// Click, drag and scroll with the mouse.
+    #[serde(default)]
+    pub mouse: bool,
+    /// Milliseconds to wait for the next key of a multi-key binding.
+    #[serde(default)]
+    pub chord_timeout: u64,
     #[serde(default)]
     pub keybindings: KeyBindings,
     #[serde(default)]
     pub styles: Styles,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub checksum: ChecksumConfig,
+    #[serde(default)]
+    pub layouts: Layouts,
+    #[serde(default)]
+    pub status_bar: StatusBarConfig,
+    /// Styles of the semantic roles, resolved from the `theme` section.
+    #[serde(skip)]
+    pub theme: Theme,
+    /// Problems found while loading the configuration files.
+    #[serde(skip)]
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 lazy_static! {
@@ -46,36 +85,60 @@ lazy_static! {
 }
 
 impl Config {
+    /// Load the configuration, invalid entries are logged and replaced by their defaults.
     pub fn new() -> Result<Self, config::ConfigError> {
-        let default_config: Config = json5::from_str(CONFIG).unwrap();
-        let data_dir = get_data_dir();
         let config_dir = get_config_dir();
-        let mut builder = config::Config::builder()
-            .set_default("data_dir", data_dir.to_str().unwrap())?
-            .set_default("config_dir", config_dir.to_str().unwrap())?;
-
-        let config_files = [
-            ("config.json5", config::FileFormat::Json5),
-            ("config.json", config::FileFormat::Json),
-            ("config.yaml", config::FileFormat::Yaml),
-            ("config.toml", config::FileFormat::Toml),
-            ("config.ini", config::FileFormat::Ini),
-        ];
-        let mut found_config = false;
-        for (file, format) in &config_files {
-            let source = config::File::from(config_dir.join(file))
-                .format(*format)
-                .required(false);
-            builder = builder.add_source(source);
-            if config_dir.join(file).exists() {
-                found_config = true
-            }
-        }
-        if !found_config {
+        if !config_files(&config_dir).any(|(path, _)| path.exists()) {
             error!("No configuration file found. Application may not behave as expected");
         }
+        let cfg = Self::load(&config_dir)?;
+        for diagnostic in &cfg.diagnostics {
+            error!("Invalid configuration: {diagnostic}");
+        }
+        Ok(cfg)
+    }
 
-        let mut cfg: Self = builder.build()?.try_deserialize()?;
+    /// Load the configuration files of `config_dir` on top of the defaults. Entries that fail to
+    /// parse are replaced by their defaults and reported in [`Config::diagnostics`].
+    pub fn load(config_dir: &Path) -> Result<Self, config::ConfigError> {
+        let default_config: Config = json5::from_str(CONFIG).map_err(|err| {
+            config::ConfigError::Message(format!("Invalid default configuration: {err}"))
+        })?;
+        let defaults = config::Config::builder()
+            .set_default("data_dir", get_data_dir().to_string_lossy().as_ref())?
+            .set_default("config_dir", config_dir.to_string_lossy().as_ref())?;
+        let mut builder = defaults.clone();
+        for (path, format) in config_files(config_dir) {
+            builder = builder.add_source(config::File::from(path).format(format).required(false));
+        }
+
+        let mut diagnostics = Vec::new();
+        let user = match builder.build() {
+            Ok(user) => user,
+            Err(err) => {
+                // A syntax error, the file is skipped entirely.
+                diagnostics.push(Diagnostic::new(None, "", err.to_string()));
+                defaults.build()?
+            }
+        };
+
+        let theme = load_theme(&user, &mut diagnostics);
+        let mut cfg = Config {
+            config: user.clone().try_deserialize()?,
+            mouse: load_section(&user, "mouse", &mut diagnostics).unwrap_or(default_config.mouse),
+            chord_timeout: load_section(&user, "chord_timeout", &mut diagnostics)
+                .unwrap_or(default_config.chord_timeout),
+            keybindings: load_keybindings(&user, &mut diagnostics),
+            styles: load_styles(&user, &theme, &mut diagnostics),
+            export: load_section(&user, "export", &mut diagnostics)
+                .unwrap_or(default_config.export),
+            checksum: load_checksum(&user, &mut diagnostics).unwrap_or(default_config.checksum),
+            layouts: load_layouts(&user, &mut diagnostics),
+            status_bar: load_section(&user, "status_bar", &mut diagnostics)
+                .unwrap_or(default_config.status_bar),
+            theme,
+            diagnostics: Vec::new(),
+        };
 
         for (mode, default_bindings) in default_config.keybindings.iter() {
             let user_bindings = cfg.keybindings.entry(*mode).or_default();
@@ -88,34 +151,330 @@ impl Config {
         for (mode, default_styles) in default_config.styles.iter() {
             let user_styles = cfg.styles.entry(*mode).or_default();
             for (style_key, style) in default_styles.iter() {
-                user_styles.entry(style_key.clone()).or_insert(*style);
+                user_styles
+                    .entry(style_key.clone())
+                    .or_insert_with(|| cfg.theme.adapt(*style));
             }
         }
+        for (mode, default_layout) in default_config.layouts.iter() {
+            cfg.layouts
+                .entry(*mode)
+                .or_insert_with(|| default_layout.clone());
+        }
 
+        cfg.diagnostics = diagnostics;
         Ok(cfg)
     }
 }
 
+/// The configuration files looked for in `config_dir`, later files take precedence.
+pub fn config_files(config_dir: &Path) -> impl Iterator<Item = (PathBuf, config::FileFormat)> {
+    [
+        ("config.json5", config::FileFormat::Json5),
+        ("config.json", config::FileFormat::Json),
+        ("config.yaml", config::FileFormat::Yaml),
+        ("config.toml", config::FileFormat::Toml),
+        ("config.ini", config::FileFormat::Ini),
+    ]
+    .into_iter()
+    .map(move |(file, format)| (config_dir.join(file), format))
+}
+
+/// An invalid configuration entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The file the entry was read from.
+    pub origin: Option<String>,
+    /// Dotted path of the entry, e.g. `keybindings.home.<ctrl-x>`.
+    pub path: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(origin: Option<&str>, path: &str, message: impl Into<String>) -> Self {
+        Self {
+            origin: origin.map(str::to_string),
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(origin) = &self.origin {
+            write!(f, "{origin}: ")?;
+        }
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// The top level entry `key` of the merged configuration.
+fn top_level(user: &config::Config, key: &str) -> Option<config::Value> {
+    // `config::Config::get` round-trips through serde, which loses the origin of values.
+    user.cache.clone().into_table().ok()?.remove(key)
+}
+
+/// The file a value was read from. Tables are created while merging files, their origin is the
+/// one of their entries.
+fn origin(value: &config::Value) -> Option<String> {
+    // Origins of files are relative to the working directory.
+    let file = |origin: &str| {
+        std::fs::canonicalize(origin)
+            .map_or_else(|_| origin.to_string(), |path| path.display().to_string())
+    };
+    value.origin().map(file).or_else(|| match &value.kind {
+        config::ValueKind::Table(table) => table.values().find_map(origin),
+        config::ValueKind::Array(array) => array.iter().find_map(origin),
+        _ => None,
+    })
+}
+
+/// The entries of `value` sorted by name, reported if `value` is not a table.
+fn sorted_entries(
+    value: config::Value,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<(String, config::Value)> {
+    let origin = origin(&value);
+    match value.into_table() {
+        Ok(table) => {
+            let mut entries: Vec<_> = table.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            entries
+        }
+        Err(err) => {
+            diagnostics.push(Diagnostic::new(origin.as_deref(), path, err.to_string()));
+            Vec::new()
+        }
+    }
+}
+
+/// The entries of the per mode table at `key`, unknown modes are reported and skipped.
+fn mode_entries(
+    user: &config::Config,
+    key: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<(Mode, String, config::Value)> {
+    let Some(table) = top_level(user, key) else {
+        return Vec::new();
+    };
+    sorted_entries(table, key, diagnostics)
+        .into_iter()
+        .filter_map(|(name, value)| {
+            // The `config` crate lowercases keys.
+            let mode = Mode::iter().find(|mode| mode.to_string().eq_ignore_ascii_case(&name));
+            if mode.is_none() {
+                diagnostics.push(Diagnostic::new(
+                    origin(&value).as_deref(),
+                    &format!("{key}.{name}"),
+                    format!("unknown mode `{name}`"),
+                ));
+            }
+            Some((mode?, format!("{key}.{name}"), value))
+        })
+        .collect()
+}
+
+fn load_keybindings(user: &config::Config, diagnostics: &mut Vec<Diagnostic>) -> KeyBindings {
+    let mut keybindings = KeyBindings::default();
+    for (mode, path, value) in mode_entries(user, "keybindings", diagnostics) {
+        for (keys, value) in sorted_entries(value, &path, diagnostics) {
+            let path = format!("{path}.{keys}");
+            let origin = origin(&value);
+            let sequence = parse_key_sequence(&keys)
+                .map_err(|err| format!("invalid key sequence `{keys}`: {err}"));
+            let action = value
+                .try_deserialize::<Action>()
+                .map_err(|err| format!("unknown action: {err}"));
+            match (sequence, action) {
+                (Ok(sequence), Ok(action)) => {
+                    keybindings
+                        .entry(mode)
+                        .or_default()
+                        .insert(sequence, action);
+                }
+                (Err(err), _) | (_, Err(err)) => {
+                    diagnostics.push(Diagnostic::new(origin.as_deref(), &path, err));
+                }
+            }
+        }
+    }
+    keybindings
+}
+
+/// Load the per-mode `styles`, colors resolve like the ones of the theme.
+fn load_styles(user: &config::Config, theme: &Theme, diagnostics: &mut Vec<Diagnostic>) -> Styles {
+    let mut styles = Styles::default();
+    for (mode, path, value) in mode_entries(user, "styles", diagnostics) {
+        for (name, value) in sorted_entries(value, &path, diagnostics) {
+            let path = format!("{path}.{name}");
+            let origin = origin(&value);
+            let style = value
+                .into_string()
+                .map_err(|err| err.to_string())
+                .and_then(|style| theme.parse_style(&style));
+            match style {
+                Ok(style) => {
+                    styles.entry(mode).or_default().insert(name, style);
+                }
+                Err(err) => diagnostics.push(Diagnostic::new(origin.as_deref(), &path, err)),
+            }
+        }
+    }
+    styles
+}
+
+fn load_layouts(user: &config::Config, diagnostics: &mut Vec<Diagnostic>) -> Layouts {
+    let mut layouts = Layouts::default();
+    for (mode, path, value) in mode_entries(user, "layouts", diagnostics) {
+        let origin = origin(&value);
+        match value.try_deserialize::<LayoutNode>() {
+            Ok(layout) => {
+                layouts.insert(mode, layout);
+            }
+            Err(err) => {
+                diagnostics.push(Diagnostic::new(origin.as_deref(), &path, err.to_string()))
+            }
+        }
+    }
+    layouts
+}
+
+/// Resolve the `theme` section, problems of its entries are reported at `theme`.
+fn load_theme(user: &config::Config, diagnostics: &mut Vec<Diagnostic>) -> Theme {
+    let config: ThemeConfig = load_section(user, "theme", diagnostics).unwrap_or_default();
+    let origin = top_level(user, "theme").and_then(|value| origin(&value));
+    let (theme, problems) = Theme::new(config);
+    for problem in problems {
+        diagnostics.push(Diagnostic::new(origin.as_deref(), "theme", problem));
+    }
+    theme
+}
+
+/// Load the `checksum` section, custom algorithms that fail to validate and rows naming an unknown
+/// algorithm are reported and left out.
+fn load_checksum(
+    user: &config::Config,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<ChecksumConfig> {
+    let mut checksum: ChecksumConfig = load_section(user, "checksum", diagnostics)?;
+    let section = top_level(user, "checksum")?;
+    let section_origin = origin(&section);
+    let table = section.into_table().unwrap_or_default();
+    // The origin of the `index`th entry of the list at `key`, or of the section.
+    let entry_origin = |key: &str, index: usize| {
+        table
+            .get(key)
+            .and_then(|list| list.clone().into_array().ok())
+            .and_then(|list| list.get(index).and_then(origin))
+            .or_else(|| section_origin.clone())
+    };
+
+    let mut index = 0;
+    checksum.custom_crc.retain(|crc| {
+        let result = crc.validate();
+        if let Err(err) = &result {
+            let path = format!("checksum.custom_crc.{index}");
+            let origin = entry_origin("custom_crc", index);
+            diagnostics.push(Diagnostic::new(origin.as_deref(), &path, err.as_str()));
+        }
+        index += 1;
+        result.is_ok()
+    });
+    let algorithms = checksum.algorithms();
+    let mut index = 0;
+    checksum.crc.retain(|name| {
+        let known = algorithms.iter().any(|crc| &crc.name == name);
+        if !known {
+            let path = format!("checksum.crc.{index}");
+            let origin = entry_origin("crc", index);
+            let message = format!("unknown CRC algorithm `{name}`");
+            diagnostics.push(Diagnostic::new(origin.as_deref(), &path, message));
+        }
+        index += 1;
+        known
+    });
+    Some(checksum)
+}
+
+/// Deserialize the section at `key`, `None` if it is missing or invalid.
+fn load_section<T: DeserializeOwned>(
+    user: &config::Config,
+    key: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<T> {
+    let value = top_level(user, key)?;
+    let origin = origin(&value);
+    value
+        .try_deserialize()
+        .map_err(|err| diagnostics.push(Diagnostic::new(origin.as_deref(), key, err.to_string())))
+        .ok()
+}
+
+/// Call `on_change` whenever one of the [`config_files`] of `config_dir` changes. Changes stop
+/// being reported when the returned watcher is dropped.
+pub fn watch(
+    config_dir: &Path,
+    on_change: impl Fn() + Send + 'static,
+) -> notify::Result<RecommendedWatcher> {
+    let files: Vec<PathBuf> = config_files(config_dir).map(|(path, _)| path).collect();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        let changed = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        );
+        // Editors often replace files, so the file names are compared rather than the paths.
+        let is_config = |path: &PathBuf| {
+            files
+                .iter()
+                .any(|file| file.file_name() == path.file_name())
+        };
+        if changed && event.paths.iter().any(is_config) {
+            on_change();
+        }
+    })?;
+    // The directory rather than the files is watched, so that files created later are seen.
+    watcher.watch(config_dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// A one line summary of `diagnostics` for the status bar, `None` if there are none.
+pub fn summarize(diagnostics: &[Diagnostic]) -> Option<String> {
+    let first = diagnostics.first()?;
+    Some(match diagnostics.len() {
+        1 => format!("Invalid configuration: {first}"),
+        count => format!(
+            "{count} invalid configuration entries, run `{} config check`. First: {first}",
+            env!("CARGO_PKG_NAME")
+        ),
+    })
+}
+
 pub fn get_data_dir() -> PathBuf {
-    let directory = if let Some(s) = DATA_FOLDER.clone() {
+    if let Some(s) = DATA_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.data_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".data")
-    };
-    directory
+    }
 }
 
 pub fn get_config_dir() -> PathBuf {
-    let directory = if let Some(s) = CONFIG_FOLDER.clone() {
+    if let Some(s) = CONFIG_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.config_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".config")
-    };
-    directory
+    }
 }
 
 fn project_directory() -> Option<ProjectDirs> {
@@ -134,22 +493,118 @@ impl<'de> Deserialize<'de> for KeyBindings {
 
         let keybindings = parsed_map
             .into_iter()
-            .map(|(mode, inner_map)| {
+            .map(|(mode, inner_map)| -> Result<_, D::Error> {
                 let converted_inner_map = inner_map
                     .into_iter()
-                    .map(|(key_str, cmd)| (parse_key_sequence(&key_str).unwrap(), cmd))
-                    .collect();
-                (mode, converted_inner_map)
+                    .map(|(key_str, cmd)| {
+                        Ok((
+                            parse_key_sequence(&key_str).map_err(de::Error::custom)?,
+                            cmd,
+                        ))
+                    })
+                    .collect::<Result<_, D::Error>>()?;
+                Ok((mode, converted_inner_map))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         Ok(KeyBindings(keybindings))
     }
 }
 
+/// Modifiers in the order they are printed, each written as `name-` in front of the key.
+const MODIFIERS: [(&str, KeyModifiers); 6] = [
+    ("ctrl", KeyModifiers::CONTROL),
+    ("shift", KeyModifiers::SHIFT),
+    ("alt", KeyModifiers::ALT),
+    ("super", KeyModifiers::SUPER),
+    ("hyper", KeyModifiers::HYPER),
+    ("meta", KeyModifiers::META),
+];
+
+/// Names of the keys that are not written as their character. The first name of a key is the one
+/// it is printed with, the others are accepted as aliases.
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("esc", KeyCode::Esc),
+    ("escape", KeyCode::Esc),
+    ("enter", KeyCode::Enter),
+    ("return", KeyCode::Enter),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("del", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("null", KeyCode::Null),
+    ("capslock", KeyCode::CapsLock),
+    ("scrolllock", KeyCode::ScrollLock),
+    ("numlock", KeyCode::NumLock),
+    ("printscreen", KeyCode::PrintScreen),
+    ("pause", KeyCode::Pause),
+    ("menu", KeyCode::Menu),
+    ("keypadbegin", KeyCode::KeypadBegin),
+    ("space", KeyCode::Char(' ')),
+    ("lt", KeyCode::Char('<')),
+    ("gt", KeyCode::Char('>')),
+    ("minus", KeyCode::Char('-')),
+    ("hyphen", KeyCode::Char('-')),
+    ("mediaplay", KeyCode::Media(MediaKeyCode::Play)),
+    ("mediapause", KeyCode::Media(MediaKeyCode::Pause)),
+    ("mediaplaypause", KeyCode::Media(MediaKeyCode::PlayPause)),
+    ("mediareverse", KeyCode::Media(MediaKeyCode::Reverse)),
+    ("mediastop", KeyCode::Media(MediaKeyCode::Stop)),
+    (
+        "mediafastforward",
+        KeyCode::Media(MediaKeyCode::FastForward),
+    ),
+    ("mediarewind", KeyCode::Media(MediaKeyCode::Rewind)),
+    ("mediatracknext", KeyCode::Media(MediaKeyCode::TrackNext)),
+    (
+        "mediatrackprevious",
+        KeyCode::Media(MediaKeyCode::TrackPrevious),
+    ),
+    ("mediarecord", KeyCode::Media(MediaKeyCode::Record)),
+    ("volumedown", KeyCode::Media(MediaKeyCode::LowerVolume)),
+    ("volumeup", KeyCode::Media(MediaKeyCode::RaiseVolume)),
+    ("mute", KeyCode::Media(MediaKeyCode::MuteVolume)),
+    ("leftshift", KeyCode::Modifier(ModifierKeyCode::LeftShift)),
+    ("leftctrl", KeyCode::Modifier(ModifierKeyCode::LeftControl)),
+    ("leftalt", KeyCode::Modifier(ModifierKeyCode::LeftAlt)),
+    ("leftsuper", KeyCode::Modifier(ModifierKeyCode::LeftSuper)),
+    ("lefthyper", KeyCode::Modifier(ModifierKeyCode::LeftHyper)),
+    ("leftmeta", KeyCode::Modifier(ModifierKeyCode::LeftMeta)),
+    ("rightshift", KeyCode::Modifier(ModifierKeyCode::RightShift)),
+    (
+        "rightctrl",
+        KeyCode::Modifier(ModifierKeyCode::RightControl),
+    ),
+    ("rightalt", KeyCode::Modifier(ModifierKeyCode::RightAlt)),
+    ("rightsuper", KeyCode::Modifier(ModifierKeyCode::RightSuper)),
+    ("righthyper", KeyCode::Modifier(ModifierKeyCode::RightHyper)),
+    ("rightmeta", KeyCode::Modifier(ModifierKeyCode::RightMeta)),
+    (
+        "isolevel3shift",
+        KeyCode::Modifier(ModifierKeyCode::IsoLevel3Shift),
+    ),
+    (
+        "isolevel5shift",
+        KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift),
+    ),
+];
+
+/// Parse the notation of a single key without brackets, e.g. `ctrl-a`, `f13` or `kp5`.
+///
+/// Modifiers and key names are case insensitive, a single character is taken as is. Keys of the
+/// keypad are prefixed with `kp`.
 fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
-    let raw_lower = raw.to_ascii_lowercase();
-    let (remaining, modifiers) = extract_modifiers(&raw_lower);
+    let (remaining, modifiers) = extract_modifiers(raw);
     parse_key_code_with_modifiers(remaining, modifiers)
 }
 
@@ -157,22 +612,15 @@ fn extract_modifiers(raw: &str) -> (&str, KeyModifiers) {
     let mut modifiers = KeyModifiers::empty();
     let mut current = raw;
 
-    loop {
-        match current {
-            rest if rest.starts_with("ctrl-") => {
-                modifiers.insert(KeyModifiers::CONTROL);
-                current = &rest[5..];
-            }
-            rest if rest.starts_with("alt-") => {
-                modifiers.insert(KeyModifiers::ALT);
-                current = &rest[4..];
-            }
-            rest if rest.starts_with("shift-") => {
-                modifiers.insert(KeyModifiers::SHIFT);
-                current = &rest[6..];
-            }
-            _ => break, // break out of the loop if no known prefix is detected
+    // Whatever follows the last modifier is the key, e.g. `-` in `ctrl--`.
+    while let Some((name, rest)) = current.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+        let name = name.to_ascii_lowercase();
+        let name = if name == "control" { "ctrl" } else { &name };
+        let Some((_, modifier)) = MODIFIERS.iter().find(|(n, _)| *n == name) else {
+            break;
         };
+        modifiers.insert(*modifier);
+        current = rest;
     }
 
     (current, modifiers)
@@ -182,140 +630,105 @@ fn parse_key_code_with_modifiers(
     raw: &str,
     mut modifiers: KeyModifiers,
 ) -> Result<KeyEvent, String> {
-    let c = match raw {
-        "esc" => KeyCode::Esc,
-        "enter" => KeyCode::Enter,
-        "left" => KeyCode::Left,
-        "right" => KeyCode::Right,
-        "up" => KeyCode::Up,
-        "down" => KeyCode::Down,
-        "home" => KeyCode::Home,
-        "end" => KeyCode::End,
-        "pageup" => KeyCode::PageUp,
-        "pagedown" => KeyCode::PageDown,
-        "backtab" => {
+    let mut state = KeyEventState::empty();
+    let mut code = parse_key_code(raw);
+    if code.is_none()
+        && let Some(key) = raw.get(2..).filter(|_| raw[..2].eq_ignore_ascii_case("kp"))
+    {
+        state.insert(KeyEventState::KEYPAD);
+        code = parse_key_code(key);
+    }
+    let code = match code.ok_or_else(|| format!("Unable to parse {raw}"))? {
+        KeyCode::BackTab => {
             modifiers.insert(KeyModifiers::SHIFT);
             KeyCode::BackTab
         }
-        "backspace" => KeyCode::Backspace,
-        "delete" => KeyCode::Delete,
-        "insert" => KeyCode::Insert,
-        "f1" => KeyCode::F(1),
-        "f2" => KeyCode::F(2),
-        "f3" => KeyCode::F(3),
-        "f4" => KeyCode::F(4),
-        "f5" => KeyCode::F(5),
-        "f6" => KeyCode::F(6),
-        "f7" => KeyCode::F(7),
-        "f8" => KeyCode::F(8),
-        "f9" => KeyCode::F(9),
-        "f10" => KeyCode::F(10),
-        "f11" => KeyCode::F(11),
-        "f12" => KeyCode::F(12),
-        "space" => KeyCode::Char(' '),
-        "hyphen" => KeyCode::Char('-'),
-        "minus" => KeyCode::Char('-'),
-        "tab" => KeyCode::Tab,
-        c if c.len() == 1 => {
-            let mut c = c.chars().next().unwrap();
-            if modifiers.contains(KeyModifiers::SHIFT) {
-                c = c.to_ascii_uppercase();
-            }
-            KeyCode::Char(c)
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyCode::Char(c.to_ascii_uppercase())
         }
-        _ => return Err(format!("Unable to parse {raw}")),
+        code => code,
     };
-    Ok(KeyEvent::new(c, modifiers))
+    Ok(KeyEvent::new_with_kind_and_state(
+        code,
+        modifiers,
+        KeyEventKind::Press,
+        state,
+    ))
 }
 
-pub fn key_event_to_string(key_event: &KeyEvent) -> String {
-    let char;
-    let key_code = match key_event.code {
-        KeyCode::Backspace => "backspace",
-        KeyCode::Enter => "enter",
-        KeyCode::Left => "left",
-        KeyCode::Right => "right",
-        KeyCode::Up => "up",
-        KeyCode::Down => "down",
-        KeyCode::Home => "home",
-        KeyCode::End => "end",
-        KeyCode::PageUp => "pageup",
-        KeyCode::PageDown => "pagedown",
-        KeyCode::Tab => "tab",
-        KeyCode::BackTab => "backtab",
-        KeyCode::Delete => "delete",
-        KeyCode::Insert => "insert",
-        KeyCode::F(c) => {
-            char = format!("f({c})");
-            &char
-        }
-        KeyCode::Char(' ') => "space",
-        KeyCode::Char(c) => {
-            char = c.to_string();
-            &char
-        }
-        KeyCode::Esc => "esc",
-        KeyCode::Null => "",
-        KeyCode::CapsLock => "",
-        KeyCode::Menu => "",
-        KeyCode::ScrollLock => "",
-        KeyCode::Media(_) => "",
-        KeyCode::NumLock => "",
-        KeyCode::PrintScreen => "",
-        KeyCode::Pause => "",
-        KeyCode::KeypadBegin => "",
-        KeyCode::Modifier(_) => "",
-    };
-
-    let mut modifiers = Vec::with_capacity(3);
-
-    if key_event.modifiers.intersects(KeyModifiers::CONTROL) {
-        modifiers.push("ctrl");
+/// A single character, a name of [`KEY_NAMES`] or a function key `f1` to `f255`.
+fn parse_key_code(raw: &str) -> Option<KeyCode> {
+    let mut chars = raw.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
     }
-
-    if key_event.modifiers.intersects(KeyModifiers::SHIFT) {
-        modifiers.push("shift");
+    let name = raw.to_ascii_lowercase();
+    if let Some((_, code)) = KEY_NAMES.iter().find(|(n, _)| *n == name) {
+        return Some(*code);
     }
+    name.strip_prefix('f')
+        .filter(|number| number.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|number| number.parse::<u8>().ok())
+        .filter(|number| *number > 0)
+        .map(KeyCode::F)
+}
 
-    if key_event.modifiers.intersects(KeyModifiers::ALT) {
-        modifiers.push("alt");
+/// The notation of `key_event` without brackets, parsed back by [`parse_key_sequence`].
+pub fn key_event_to_string(key_event: &KeyEvent) -> String {
+    let mut key = String::new();
+    for (name, modifier) in MODIFIERS {
+        if key_event.modifiers.contains(modifier) {
+            key.push_str(name);
+            key.push('-');
+        }
     }
-
-    let mut key = modifiers.join("-");
-
-    if !key.is_empty() {
-        key.push('-');
+    if key_event.state.contains(KeyEventState::KEYPAD) {
+        key.push_str("kp");
+    }
+    match key_event.code {
+        KeyCode::F(n) => key.push_str(&format!("f{n}")),
+        code => match KEY_NAMES.iter().find(|(_, c)| *c == code) {
+            Some((name, _)) => key.push_str(name),
+            None => {
+                if let KeyCode::Char(c) = code {
+                    key.push(c);
+                }
+            }
+        },
     }
-    key.push_str(key_code);
-
     key
 }
 
+/// The notation of `keys` as written in the configuration, e.g. `<g><g>`.
+pub fn key_sequence_to_string(keys: &[KeyEvent]) -> String {
+    keys.iter()
+        .map(|key| format!("<{}>", key_event_to_string(key)))
+        .collect()
+}
+
+/// Parse a sequence of keys in brackets, e.g. `<g><g>` or `<space> <c> <h>`, or a single key
+/// without brackets.
 pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
-    if raw.chars().filter(|c| *c == '>').count() != raw.chars().filter(|c| *c == '<').count() {
-        return Err(format!("Unable to parse `{}`", raw));
+    if !raw.starts_with('<') {
+        return parse_key_event(raw).map(|key| vec![key]);
     }
-    let raw = if !raw.contains("><") {
-        let raw = raw.strip_prefix('<').unwrap_or(raw);
-        let raw = raw.strip_prefix('>').unwrap_or(raw);
-        raw
-    } else {
-        raw
-    };
-    let sequences = raw
-        .split("><")
-        .map(|seq| {
-            if let Some(s) = seq.strip_prefix('<') {
-                s
-            } else if let Some(s) = seq.strip_suffix('>') {
-                s
-            } else {
-                seq
-            }
-        })
-        .collect::<Vec<_>>();
-
-    sequences.into_iter().map(parse_key_event).collect()
+    let mut keys = Vec::new();
+    let mut rest = raw;
+    while let Some(group) = rest.strip_prefix('<') {
+        // The key may be `<` or `>` itself, so the closing bracket is searched after its first
+        // character.
+        let (key, _) = extract_modifiers(group);
+        let key_start = group.len() - key.len() + key.chars().next().map_or(0, char::len_utf8);
+        let end = group[key_start..]
+            .find('>')
+            .ok_or_else(|| format!("Missing `>` in `{raw}`"))?;
+        keys.push(parse_key_event(&group[..key_start + end])?);
+        rest = group[key_start + end + 1..].trim_start();
+    }
+    if !rest.is_empty() {
+        return Err(format!("Unable to parse `{raw}`"));
+    }
+    Ok(keys)
 }
 
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
@@ -330,190 +743,195 @@ impl<'de> Deserialize<'de> for Styles {
 
         let styles = parsed_map
             .into_iter()
-            .map(|(mode, inner_map)| {
+            .map(|(mode, inner_map)| -> Result<_, D::Error> {
                 let converted_inner_map = inner_map
                     .into_iter()
-                    .map(|(str, style)| (str, parse_style(&style)))
-                    .collect();
-                (mode, converted_inner_map)
+                    .map(|(str, style)| {
+                        Ok((
+                            str,
+                            try_parse_style(&style, &HashMap::new()).map_err(de::Error::custom)?,
+                        ))
+                    })
+                    .collect::<Result<_, D::Error>>()?;
+                Ok((mode, converted_inner_map))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         Ok(Styles(styles))
     }
 }
 
-pub fn parse_style(line: &str) -> Style {
-    let (foreground, background) =
-        line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
-    let foreground = process_color_string(foreground);
-    let background = process_color_string(&background.replace("on ", ""));
-
-    let mut style = Style::default();
-    if let Some(fg) = parse_color(&foreground.0) {
-        style = style.fg(fg);
-    }
-    if let Some(bg) = parse_color(&background.0) {
-        style = style.bg(bg);
-    }
-    style = style.add_modifier(foreground.1 | background.1);
-    style
-}
-
-fn process_color_string(color_str: &str) -> (String, Modifier) {
-    let color = color_str
-        .replace("grey", "gray")
-        .replace("bright ", "")
-        .replace("bold ", "")
-        .replace("underline ", "")
-        .replace("inverse ", "");
-
-    let mut modifiers = Modifier::empty();
-    if color_str.contains("underline") {
-        modifiers |= Modifier::UNDERLINED;
-    }
-    if color_str.contains("bold") {
-        modifiers |= Modifier::BOLD;
-    }
-    if color_str.contains("inverse") {
-        modifiers |= Modifier::REVERSED;
-    }
-
-    (color, modifiers)
-}
-
-fn parse_color(s: &str) -> Option<Color> {
-    let s = s.trim_start();
-    let s = s.trim_end();
-    if s.contains("bright color") {
-        let s = s.trim_start_matches("bright ");
-        let c = s
-            .trim_start_matches("color")
-            .parse::<u8>()
-            .unwrap_or_default();
-        Some(Color::Indexed(c.wrapping_shl(8)))
-    } else if s.contains("color") {
-        let c = s
-            .trim_start_matches("color")
-            .parse::<u8>()
-            .unwrap_or_default();
-        Some(Color::Indexed(c))
-    } else if s.contains("gray") {
-        let c = 232
-            + s.trim_start_matches("gray")
-                .parse::<u8>()
-                .unwrap_or_default();
-        Some(Color::Indexed(c))
-    } else if s.contains("rgb") {
-        let red = (s.as_bytes()[3] as char).to_digit(10).unwrap_or_default() as u8;
-        let green = (s.as_bytes()[4] as char).to_digit(10).unwrap_or_default() as u8;
-        let blue = (s.as_bytes()[5] as char).to_digit(10).unwrap_or_default() as u8;
-        let c = 16 + red * 36 + green * 6 + blue;
-        Some(Color::Indexed(c))
-    } else if s == "bold black" {
-        Some(Color::Indexed(8))
-    } else if s == "bold red" {
-        Some(Color::Indexed(9))
-    } else if s == "bold green" {
-        Some(Color::Indexed(10))
-    } else if s == "bold yellow" {
-        Some(Color::Indexed(11))
-    } else if s == "bold blue" {
-        Some(Color::Indexed(12))
-    } else if s == "bold magenta" {
-        Some(Color::Indexed(13))
-    } else if s == "bold cyan" {
-        Some(Color::Indexed(14))
-    } else if s == "bold white" {
-        Some(Color::Indexed(15))
-    } else if s == "black" {
-        Some(Color::Indexed(0))
-    } else if s == "red" {
-        Some(Color::Indexed(1))
-    } else if s == "green" {
-        Some(Color::Indexed(2))
-    } else if s == "yellow" {
-        Some(Color::Indexed(3))
-    } else if s == "blue" {
-        Some(Color::Indexed(4))
-    } else if s == "magenta" {
-        Some(Color::Indexed(5))
-    } else if s == "cyan" {
-        Some(Color::Indexed(6))
-    } else if s == "white" {
-        Some(Color::Indexed(7))
-    } else {
-        None
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     use super::*;
+    use crate::action::CharacterInput;
+    use crate::theme::Role;
 
     #[test]
-    fn test_parse_style_default() {
-        let style = parse_style("");
-        assert_eq!(style, Style::default());
+    fn test_config() -> Result<()> {
+        let c = Config::new()?;
+        assert_eq!(
+            c.keybindings
+                .get(&Mode::Home)
+                .unwrap()
+                .get(&parse_key_sequence("<q>").unwrap_or_default())
+                .unwrap(),
+            &Action::Quit
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_parse_style_foreground() {
-        let style = parse_style("red");
-        assert_eq!(style.fg, Some(Color::Indexed(1)));
+    fn test_config_character_input() -> Result<()> {
+        let c = Config::new()?;
+        assert_eq!(
+            c.keybindings
+                .get(&Mode::Home)
+                .unwrap()
+                .get(&parse_key_sequence("<f>").unwrap_or_default())
+                .unwrap(),
+            &Action::CharacterInput(CharacterInput::Fifteen)
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_parse_style_background() {
-        let style = parse_style("on blue");
-        assert_eq!(style.bg, Some(Color::Indexed(4)));
+    fn test_config_bitwise_mode() -> Result<()> {
+        let c = Config::new()?;
+        assert_eq!(
+            c.keybindings
+                .get(&Mode::Bitwise)
+                .unwrap()
+                .get(&parse_key_sequence("<esc>").unwrap_or_default())
+                .unwrap(),
+            &Action::SwitchMode(Mode::Home)
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_parse_style_modifiers() {
-        let style = parse_style("underline red on blue");
-        assert_eq!(style.fg, Some(Color::Indexed(1)));
-        assert_eq!(style.bg, Some(Color::Indexed(4)));
-    }
+    fn test_load_reports_invalid_entries() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("config.json5");
+        std::fs::write(
+            &file,
+            r#"{
+                "keybindings": {
+                    "Home": { "<ctrl-q>": "Quit", "<ctrl-nope>": "Quit", "<x>": "Explode" },
+                    "Homer": { "<q>": "Quit" },
+                },
+                "styles": { "Home": { "focus": "bluish on red" } },
+                "export": { "per_line": "many" },
+                "checksum": {
+                    "crc": ["CRC-32/ISO-HDLC", "CRC-99/NOPE", "CRC-8/WIDE"],
+                    "custom_crc": [{ "name": "CRC-8/WIDE", "width": 8, "poly": "0x1ff" }],
+                },
+            }"#,
+        )?;
+        let c = Config::load(dir.path())?;
+        let paths: Vec<&str> = c.diagnostics.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "keybindings.homer",
+                "keybindings.home.<ctrl-nope>",
+                "keybindings.home.<x>",
+                "styles.home.focus",
+                "export",
+                "checksum.custom_crc.0",
+                "checksum.crc.1",
+                "checksum.crc.2",
+            ]
+        );
+        let origin = file.canonicalize()?.display().to_string();
+        assert!(
+            c.diagnostics
+                .iter()
+                .all(|d| d.origin.as_ref() == Some(&origin))
+        );
 
-    #[test]
-    fn test_process_color_string() {
-        let (color, modifiers) = process_color_string("underline bold inverse gray");
-        assert_eq!(color, "gray");
-        assert!(modifiers.contains(Modifier::UNDERLINED));
-        assert!(modifiers.contains(Modifier::BOLD));
-        assert!(modifiers.contains(Modifier::REVERSED));
+        // Valid entries apply, broken ones fall back to the defaults.
+        let home = &c.keybindings[&Mode::Home];
+        assert_eq!(home[&parse_key_sequence("<ctrl-q>").unwrap()], Action::Quit);
+        assert_eq!(
+            home[&parse_key_sequence("<x>").unwrap()],
+            Action::ToggleExport
+        );
+        let focus = c.styles.get(&Mode::Home).and_then(|s| s.get("focus"));
+        assert_eq!(focus, None);
+        assert_eq!(c.export.per_line, 12);
+        assert_eq!(c.checksum.crc, ["CRC-32/ISO-HDLC"]);
+        assert!(c.checksum.custom_crc.is_empty());
+        Ok(())
     }
 
     #[test]
-    fn test_parse_color_rgb() {
-        let color = parse_color("rgb123");
-        let expected = 16 + 36 + 2 * 6 + 3;
-        assert_eq!(color, Some(Color::Indexed(expected)));
+    fn test_load_theme() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("config.json5"),
+            r#"{ theme: { name: "gruvbox", colors: "TrueColor", roles: { error: "bluish" } } }"#,
+        )?;
+        let c = Config::load(dir.path())?;
+        assert_eq!(c.theme.config.name, "gruvbox");
+        assert_eq!(c.diagnostics.len(), 1);
+        assert_eq!(c.diagnostics[0].path, "theme");
+        // The broken role keeps the style of the theme.
+        assert_eq!(
+            c.theme.style(Role::Error).fg,
+            Some(ratatui::style::Color::Rgb(0xfb, 0x49, 0x34))
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_parse_color_unknown() {
-        let color = parse_color("unknown");
-        assert_eq!(color, None);
+    fn test_load_styles_use_the_theme_palette() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("config.json5"),
+            r##"{
+                theme: { name: "gruvbox", colors: "Indexed", palette: { accent: "#ff0000" } },
+                styles: { Home: { focus: "bold aqua on accent" } },
+            }"##,
+        )?;
+        let c = Config::load(dir.path())?;
+        assert!(c.diagnostics.is_empty());
+        let focus = c.styles[&Mode::Home]["focus"];
+        assert_eq!(focus.fg, c.theme.style(Role::HighlightBit).fg);
+        assert_eq!(focus.bg, Some(ratatui::style::Color::Indexed(196)));
+        Ok(())
     }
 
     #[test]
-    fn test_config() -> Result<()> {
-        let c = Config::new()?;
+    fn test_load_reports_syntax_errors() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("config.json5"), "{ keybindings: ")?;
+        let c = Config::load(dir.path())?;
+        assert_eq!(c.diagnostics.len(), 1);
         assert_eq!(
-            c.keybindings
-                .get(&Mode::Home)
-                .unwrap()
-                .get(&parse_key_sequence("<q>").unwrap_or_default())
-                .unwrap(),
-            &Action::Quit
+            c.keybindings[&Mode::Home][&parse_key_sequence("<q>").unwrap()],
+            Action::Quit
         );
         Ok(())
     }
 
+    #[test]
+    fn test_watch_reports_config_changes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        let _watcher = watch(dir.path(), move || {
+            let _ = tx.send(());
+        })?;
+        let timeout = std::time::Duration::from_secs(5);
+        std::fs::write(dir.path().join("notes.txt"), "unrelated")?;
+        std::fs::write(dir.path().join("config.json5"), "{}")?;
+        assert!(rx.recv_timeout(timeout).is_ok());
+        Ok(())
+    }
+
     #[test]
     fn test_simple_keys() {
         assert_eq!(
@@ -595,4 +1013,109 @@ mod tests {
             KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)
         );
     }
+
+    #[test]
+    fn test_extended_keys() {
+        assert_eq!(
+            parse_key_event("super-hyper-meta-x").unwrap(),
+            KeyEvent::new(
+                KeyCode::Char('x'),
+                KeyModifiers::SUPER | KeyModifiers::HYPER | KeyModifiers::META
+            )
+        );
+        assert_eq!(
+            parse_key_event("f13").unwrap(),
+            KeyEvent::new(KeyCode::F(13), KeyModifiers::empty())
+        );
+        assert_eq!(
+            parse_key_event("kp5").unwrap(),
+            KeyEvent::new_with_kind_and_state(
+                KeyCode::Char('5'),
+                KeyModifiers::empty(),
+                KeyEventKind::Press,
+                KeyEventState::KEYPAD
+            )
+        );
+        assert_eq!(
+            parse_key_event("MediaPlayPause").unwrap(),
+            KeyEvent::new(
+                KeyCode::Media(MediaKeyCode::PlayPause),
+                KeyModifiers::empty()
+            )
+        );
+        assert!(parse_key_event("f0").is_err());
+        assert!(parse_key_event("f256").is_err());
+        assert_eq!(
+            key_event_to_string(&KeyEvent::new(KeyCode::F(1), KeyModifiers::empty())),
+            "f1"
+        );
+    }
+
+    #[test]
+    fn test_angle_brackets() {
+        let lt = KeyEvent::new(KeyCode::Char('<'), KeyModifiers::empty());
+        let gt = KeyEvent::new(KeyCode::Char('>'), KeyModifiers::empty());
+        assert_eq!(parse_key_sequence("<<>").unwrap(), [lt]);
+        assert_eq!(parse_key_sequence("<>>").unwrap(), [gt]);
+        assert_eq!(parse_key_sequence("<lt><gt>").unwrap(), [lt, gt]);
+        assert_eq!(
+            parse_key_sequence("<ctrl->>").unwrap(),
+            [KeyEvent::new(KeyCode::Char('>'), KeyModifiers::CONTROL)]
+        );
+        assert_eq!(key_sequence_to_string(&[lt, gt]), "<lt><gt>");
+        assert!(parse_key_sequence("<>").is_err());
+        assert!(parse_key_sequence("<a>b").is_err());
+    }
+
+    #[test]
+    fn test_sequence_with_spaces() {
+        assert_eq!(
+            parse_key_sequence("<space> <c> <h>").unwrap(),
+            parse_key_sequence("<space><c><h>").unwrap()
+        );
+    }
+
+    /// Key events as the parser produces them: `backtab` always comes with shift and shifted
+    /// letters are upper case.
+    fn key_event() -> impl Strategy<Value = KeyEvent> {
+        let code = prop_oneof![
+            any::<char>().prop_map(KeyCode::Char),
+            (1..=u8::MAX).prop_map(KeyCode::F),
+            proptest::sample::select(KEY_NAMES.iter().map(|(_, code)| *code).collect::<Vec<_>>()),
+        ];
+        (code, any::<u8>(), any::<bool>()).prop_map(|(code, bits, keypad)| {
+            let mut modifiers = KeyModifiers::from_bits_truncate(bits);
+            let code = match code {
+                KeyCode::BackTab => {
+                    modifiers.insert(KeyModifiers::SHIFT);
+                    code
+                }
+                KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                }
+                code => code,
+            };
+            let state = if keypad {
+                KeyEventState::KEYPAD
+            } else {
+                KeyEventState::empty()
+            };
+            KeyEvent::new_with_kind_and_state(code, modifiers, KeyEventKind::Press, state)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_key_sequences_round_trip(keys in proptest::collection::vec(key_event(), 1..5)) {
+            let raw = key_sequence_to_string(&keys);
+            prop_assert_eq!(parse_key_sequence(&raw), Ok(keys), "{}", raw);
+        }
+
+        #[test]
+        fn test_parsed_keys_print_back(raw in "(<[a-zA-Z0-9<>-]{1,12}> ?){1,4}|\\PC*") {
+            if let Ok(keys) = parse_key_sequence(&raw) {
+                prop_assert_eq!(parse_key_sequence(&key_sequence_to_string(&keys)), Ok(keys));
+            }
+        }
+    }
 }
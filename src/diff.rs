@@ -0,0 +1,161 @@
+//! Byte-by-byte and bit-by-bit comparison of two buffers A and B, with B shifted against A.
+//!
+//! A shift of `n` places the first byte of B at offset `n` of A, so byte `i` of B is compared
+//! with byte `i + n` of A. Bytes of either buffer without a counterpart are unmatched.
+
+use std::ops::Range;
+
+/// Shifts tried in each direction when looking for the best alignment.
+pub const MAX_SHIFT: usize = 64;
+
+/// Pairs compared per shift when looking for the best alignment, from the start of the overlap.
+const ALIGN_WINDOW: usize = 64 * 1024;
+
+/// What differs between two buffers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    /// Pairs of bytes compared, where the buffers overlap.
+    pub compared: usize,
+    /// Pairs that differ.
+    pub differing: usize,
+    /// Offset in A of the first pair that differs.
+    pub first: Option<usize>,
+    /// Bits that differ between the pairs.
+    pub hamming: u64,
+    /// Bytes of either buffer outside of the overlap.
+    pub unmatched: usize,
+}
+
+impl Summary {
+    pub fn is_identical(&self) -> bool {
+        self.differing == 0 && self.unmatched == 0
+    }
+}
+
+/// Positions relative to the start of A covering both buffers, one per column of a side by side
+/// view. Position `p` shows byte `p` of A and byte `p - shift` of B, where they exist.
+pub fn positions(a_len: usize, b_len: usize, shift: isize) -> Range<isize> {
+    let start = shift.min(0);
+    let end = (a_len as isize).max(shift + b_len as isize);
+    start..end.max(start)
+}
+
+/// The byte of `a` and of `b` at `position`, see [`positions`].
+pub fn at(a: &[u8], b: &[u8], shift: isize, position: isize) -> (Option<u8>, Option<u8>) {
+    let get = |bytes: &[u8], offset: isize| {
+        usize::try_from(offset)
+            .ok()
+            .and_then(|offset| bytes.get(offset).copied())
+    };
+    (get(a, position), get(b, position - shift))
+}
+
+pub fn summarize(a: &[u8], b: &[u8], shift: isize) -> Summary {
+    let mut summary = Summary::default();
+    for position in positions(a.len(), b.len(), shift) {
+        match at(a, b, shift, position) {
+            (Some(x), Some(y)) => {
+                summary.compared += 1;
+                if x != y {
+                    summary.differing += 1;
+                    summary.first.get_or_insert(position as usize);
+                    summary.hamming += u64::from((x ^ y).count_ones());
+                }
+            }
+            (None, None) => {}
+            _ => summary.unmatched += 1,
+        }
+    }
+    summary
+}
+
+/// The shift within `-max..=max` leaving the fewest bytes differing or unmatched, the smallest
+/// one of equally good shifts.
+///
+/// Only the first [`ALIGN_WINDOW`] pairs of the overlap are compared, and a shift is given up once
+/// it is no better than the best one so far, so that large buffers align at once.
+pub fn best_shift(a: &[u8], b: &[u8], max: usize) -> isize {
+    let max = max as isize;
+    let mut best: Option<(usize, isize)> = None;
+    for shift in (0..=max).flat_map(|distance| [distance, -distance]) {
+        let positions = positions(a.len(), b.len(), shift);
+        let overlap = shift.max(0)..(a.len() as isize).min(shift + b.len() as isize);
+        let limit = best.map_or(usize::MAX, |(cost, _)| cost);
+        let mut cost = positions.len() - overlap.len();
+        for position in overlap.take(ALIGN_WINDOW) {
+            if cost >= limit {
+                break;
+            }
+            if a[position as usize] != b[(position - shift) as usize] {
+                cost += 1;
+            }
+        }
+        if cost < limit {
+            best = Some((cost, shift));
+        }
+    }
+    best.map_or(0, |(_, shift)| shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_summarize_aligned() {
+        let a = [0xde, 0xad, 0xbe, 0xef];
+        let b = [0xde, 0xac, 0xbe, 0x10, 0xff];
+        assert_eq!(
+            summarize(&a, &b, 0),
+            Summary {
+                compared: 4,
+                differing: 2,
+                first: Some(1),
+                hamming: 1 + 8,
+                unmatched: 1,
+            }
+        );
+        assert!(summarize(&a, &a, 0).is_identical());
+        assert!(summarize(&[], &[], 0).is_identical());
+    }
+
+    #[test]
+    fn test_summarize_shifted() {
+        // B is A behind a two byte header.
+        let a = [1, 2, 3, 4];
+        let b = [0xaa, 0xbb, 1, 2, 3, 4];
+        assert_eq!(positions(a.len(), b.len(), -2), -2..4);
+        assert_eq!(at(&a, &b, -2, -1), (None, Some(0xbb)));
+        assert_eq!(at(&a, &b, -2, 0), (Some(1), Some(1)));
+        let summary = summarize(&a, &b, -2);
+        assert_eq!((summary.compared, summary.differing), (4, 0));
+        assert_eq!(summary.unmatched, 2);
+
+        assert_eq!(positions(2, 2, 5), 0..7);
+        assert_eq!(summarize(&[1, 2], &[1, 2], 5).unmatched, 4);
+    }
+
+    #[test]
+    fn test_best_shift() {
+        let a = [1, 2, 3, 4, 5, 6];
+        assert_eq!(
+            best_shift(&a, &[0xaa, 0xbb, 1, 2, 3, 4, 5, 6], MAX_SHIFT),
+            -2
+        );
+        assert_eq!(best_shift(&a, &[3, 4, 5, 6], MAX_SHIFT), 2);
+        assert_eq!(best_shift(&a, &[3, 4, 5, 6], 1), 0);
+        // Nothing matches anywhere, the buffers stay aligned.
+        assert_eq!(best_shift(&[1, 1], &[2, 2], MAX_SHIFT), 0);
+        assert_eq!(best_shift(&[], &[], MAX_SHIFT), 0);
+    }
+
+    #[test]
+    fn test_best_shift_of_large_buffers() {
+        let a: Vec<u8> = (0..=250).cycle().take(8 << 20).collect();
+        let mut b = vec![0xaa; 3];
+        b.extend(&a);
+        assert_eq!(best_shift(&a, &b, MAX_SHIFT), -3);
+    }
+}
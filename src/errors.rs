@@ -15,10 +15,12 @@ pub fn init() -> Result<()> {
         .into_hooks();
     eyre_hook.install()?;
     std::panic::set_hook(Box::new(move |panic_info| {
-        if let Ok(mut t) = crate::tui::Tui::new() {
-            if let Err(r) = t.exit() {
-                error!("Unable to exit Terminal: {:?}", r);
-            }
+        // Before anything else that might fail, the session is what the user stands to lose.
+        let recovery = crate::recovery::save_on_panic();
+        if let Ok(mut t) = crate::tui::Tui::new()
+            && let Err(r) = t.exit()
+        {
+            error!("Unable to exit Terminal: {:?}", r);
         }
 
         #[cfg(not(debug_assertions))]
@@ -44,6 +46,14 @@ pub fn init() -> Result<()> {
                 .create_panic_handler()(panic_info);
         }
 
+        // Last, so it is not scrolled away by the stack trace.
+        if let Some(path) = crate::logging::log_path() {
+            eprintln!("The log of this session is at {}", path.display());
+        }
+        if recovery.is_some() {
+            eprintln!("The session was saved, restart to restore it");
+        }
+
         std::process::exit(libc::EXIT_FAILURE);
     }));
     Ok(())
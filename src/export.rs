@@ -0,0 +1,338 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+use crate::interpret::Endianness;
+
+/// Source-code syntax a byte buffer can be rendered as.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum LiteralFormat {
+    #[default]
+    #[strum(to_string = "C")]
+    C,
+    #[strum(to_string = "Rust array")]
+    RustArray,
+    #[strum(to_string = "Rust b\"\"")]
+    RustByteString,
+    #[strum(to_string = "Python")]
+    Python,
+    #[strum(to_string = "Go")]
+    Go,
+    #[strum(to_string = "JavaScript")]
+    JavaScript,
+}
+
+impl LiteralFormat {
+    /// Byte-string literals have no notion of wider elements and always emit single bytes.
+    pub fn is_byte_string(&self) -> bool {
+        matches!(self, LiteralFormat::RustByteString | LiteralFormat::Python)
+    }
+}
+
+/// Width of a single element of an exported array.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum ElementWidth {
+    #[default]
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl ElementWidth {
+    pub fn bytes(&self) -> usize {
+        match self {
+            ElementWidth::U8 => 1,
+            ElementWidth::U16 => 2,
+            ElementWidth::U32 => 4,
+            ElementWidth::U64 => 8,
+        }
+    }
+
+    fn bits(&self) -> usize {
+        self.bytes() * 8
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ExportConfig {
+    /// Identifier used for the declared variable.
+    #[serde(default = "ExportConfig::default_name")]
+    pub name: String,
+    /// Number of elements per line, `0` disables wrapping.
+    #[serde(default = "ExportConfig::default_per_line")]
+    pub per_line: usize,
+    #[serde(default)]
+    pub element_width: ElementWidth,
+    /// Byte order used to combine bytes into elements wider than one byte.
+    #[serde(default)]
+    pub endianness: Endianness,
+    #[serde(default)]
+    pub format: LiteralFormat,
+}
+
+impl ExportConfig {
+    fn default_name() -> String {
+        "data".to_string()
+    }
+
+    fn default_per_line() -> usize {
+        12
+    }
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            name: Self::default_name(),
+            per_line: Self::default_per_line(),
+            element_width: ElementWidth::default(),
+            endianness: Endianness::default(),
+            format: LiteralFormat::default(),
+        }
+    }
+}
+
+const INDENT: &str = "    ";
+
+/// Render `bytes` as a literal in the given `format`.
+///
+/// Trailing bytes that do not fill a complete element are zero-padded.
+pub fn export(bytes: &[u8], format: LiteralFormat, config: &ExportConfig) -> String {
+    if format.is_byte_string() {
+        return export_byte_string(bytes, format, config);
+    }
+    let width = config.element_width;
+    let elements = elements(bytes, width, config.endianness);
+    let name = &config.name;
+    let len = elements.len();
+    let (open, close) = match format {
+        LiteralFormat::C => (
+            format!("uint{}_t {name}[{len}] = {{", width.bits()),
+            "};".to_string(),
+        ),
+        LiteralFormat::RustArray => (
+            format!("let {name}: [u{}; {len}] = [", width.bits()),
+            "];".to_string(),
+        ),
+        LiteralFormat::Go => {
+            let ty = match width {
+                ElementWidth::U8 => "byte".to_string(),
+                _ => format!("uint{}", width.bits()),
+            };
+            (format!("{name} := []{ty}{{"), "}".to_string())
+        }
+        LiteralFormat::JavaScript => {
+            let ty = match width {
+                ElementWidth::U64 => "BigUint64Array".to_string(),
+                _ => format!("Uint{}Array", width.bits()),
+            };
+            (format!("const {name} = new {ty}(["), "]);".to_string())
+        }
+        LiteralFormat::RustByteString | LiteralFormat::Python => unreachable!(),
+    };
+    let suffix = if format == LiteralFormat::JavaScript && width == ElementWidth::U64 {
+        "n"
+    } else {
+        ""
+    };
+    let digits = width.bytes() * 2;
+    let items: Vec<String> = elements
+        .iter()
+        .map(|e| format!("0x{e:0digits$x}{suffix}"))
+        .collect();
+
+    let mut out = open;
+    if items.is_empty() {
+        out.push_str(&close);
+        return out;
+    }
+    let per_line = if config.per_line == 0 {
+        items.len()
+    } else {
+        config.per_line
+    };
+    out.push('\n');
+    for line in items.chunks(per_line) {
+        let _ = writeln!(out, "{INDENT}{},", line.join(", "));
+    }
+    out.push_str(&close);
+    out
+}
+
+fn export_byte_string(bytes: &[u8], format: LiteralFormat, config: &ExportConfig) -> String {
+    let lines: Vec<String> = if config.per_line == 0 || bytes.is_empty() {
+        vec![escape(bytes, format)]
+    } else {
+        bytes
+            .chunks(config.per_line)
+            .map(|chunk| escape(chunk, format))
+            .collect()
+    };
+    let name = &config.name;
+    match format {
+        LiteralFormat::RustByteString => {
+            // A trailing backslash continues the literal on the next line and skips the
+            // indentation, so the bytes are unaffected by wrapping. It would skip a leading space
+            // of the line as well, which is escaped instead.
+            let lines: Vec<String> = lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| match line.strip_prefix(' ') {
+                    Some(rest) if i > 0 => format!("\\x20{rest}"),
+                    _ => line.clone(),
+                })
+                .collect();
+            let body = lines.join(&format!("\\\n{INDENT}"));
+            format!("let {name}: &[u8] = b\"{body}\";")
+        }
+        LiteralFormat::Python if lines.len() == 1 => format!("{name} = b'{}'", lines[0]),
+        LiteralFormat::Python => {
+            let mut out = format!("{name} = (\n");
+            for line in lines {
+                let _ = writeln!(out, "{INDENT}b'{line}'");
+            }
+            out.push(')');
+            out
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn escape(bytes: &[u8], format: LiteralFormat) -> String {
+    let quote = if format == LiteralFormat::Python {
+        b'\''
+    } else {
+        b'"'
+    };
+    let mut out = String::with_capacity(bytes.len() * 4);
+    for &b in bytes {
+        match b {
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            b if b == quote => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            0x20..=0x7e => out.push(b as char),
+            _ => {
+                let _ = write!(out, "\\x{b:02x}");
+            }
+        }
+    }
+    out
+}
+
+fn elements(bytes: &[u8], width: ElementWidth, endianness: Endianness) -> Vec<u64> {
+    bytes
+        .chunks(width.bytes())
+        .map(|chunk| {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            let word = &word[..width.bytes()];
+            let fold = |acc: u64, b: &u8| (acc << 8) | u64::from(*b);
+            match endianness {
+                Endianness::Big => word.iter().fold(0, fold),
+                Endianness::Little => word.iter().rev().fold(0, fold),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn config(per_line: usize, element_width: ElementWidth) -> ExportConfig {
+        ExportConfig {
+            per_line,
+            element_width,
+            ..ExportConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_export_c() {
+        let out = export(
+            &[0xde, 0xad, 0xbe, 0xef, 0x01],
+            LiteralFormat::C,
+            &config(4, ElementWidth::U8),
+        );
+        assert_eq!(
+            out,
+            "uint8_t data[5] = {\n    0xde, 0xad, 0xbe, 0xef,\n    0x01,\n};"
+        );
+    }
+
+    #[test]
+    fn test_export_element_width() {
+        let mut cfg = config(0, ElementWidth::U16);
+        let out = export(&[0x01, 0x02, 0x03], LiteralFormat::RustArray, &cfg);
+        assert_eq!(out, "let data: [u16; 2] = [\n    0x0201, 0x0003,\n];");
+
+        cfg.endianness = Endianness::Big;
+        let out = export(&[0x01, 0x02, 0x03], LiteralFormat::Go, &cfg);
+        assert_eq!(out, "data := []uint16{\n    0x0102, 0x0300,\n}");
+    }
+
+    #[test]
+    fn test_export_javascript_bigint() {
+        let out = export(
+            &[1],
+            LiteralFormat::JavaScript,
+            &config(0, ElementWidth::U64),
+        );
+        assert_eq!(
+            out,
+            "const data = new BigUint64Array([\n    0x0000000000000001n,\n]);"
+        );
+    }
+
+    #[test]
+    fn test_export_empty() {
+        let out = export(&[], LiteralFormat::Go, &ExportConfig::default());
+        assert_eq!(out, "data := []byte{}");
+    }
+
+    #[test]
+    fn test_export_rust_byte_string() {
+        let out = export(
+            b"ab\"\\\n\x00cd",
+            LiteralFormat::RustByteString,
+            &config(4, ElementWidth::U32),
+        );
+        assert_eq!(out, "let data: &[u8] = b\"ab\\\"\\\\\\\n    \\n\\x00cd\";");
+    }
+
+    #[test]
+    fn test_export_rust_byte_string_keeps_leading_spaces() {
+        // A line continuation skips the spaces at the start of the next line.
+        let out = export(
+            b"a  b",
+            LiteralFormat::RustByteString,
+            &config(1, ElementWidth::U8),
+        );
+        assert_eq!(
+            out,
+            "let data: &[u8] = b\"a\\\n    \\x20\\\n    \\x20\\\n    b\";"
+        );
+    }
+
+    #[test]
+    fn test_export_python() {
+        let out = export(b"it's", LiteralFormat::Python, &config(0, ElementWidth::U8));
+        assert_eq!(out, "data = b'it\\'s'");
+
+        let out = export(
+            b"abcdef",
+            LiteralFormat::Python,
+            &config(4, ElementWidth::U8),
+        );
+        assert_eq!(out, "data = (\n    b'abcd'\n    b'ef'\n)");
+    }
+}
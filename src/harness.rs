@@ -0,0 +1,266 @@
+//! Headless runs of the [`App`] for tests: a scripted sequence of events on a [`TestBackend`].
+
+use std::path::Path;
+
+use color_eyre::Result;
+use ratatui::{backend::TestBackend, buffer::Buffer};
+
+use crate::{
+    app::App,
+    config::{Config, parse_key_sequence},
+    recording::{self, Recorder},
+    tui::{Event, ScriptedEvents, Tui},
+};
+
+/// Events of the keys written in the key notation of the configuration, e.g. `<F2><x>`. A string
+/// without brackets is typed key by key.
+pub fn keys(notation: &str) -> Vec<Event> {
+    let keys = if notation.starts_with('<') {
+        parse_key_sequence(notation).expect("valid key notation")
+    } else {
+        notation
+            .chars()
+            .flat_map(|c| parse_key_sequence(&c.to_string()).expect("valid key"))
+            .collect()
+    };
+    keys.into_iter().map(Event::Key).collect()
+}
+
+/// Run an app with the default configuration on a `width` x `height` screen through `events`,
+/// followed by a render. Returns the last rendered screen.
+pub async fn run(width: u16, height: u16, events: Vec<Event>) -> Result<Buffer> {
+    let mut app = app()?;
+    run_app(&mut app, width, height, events).await
+}
+
+/// An app with the default configuration.
+pub fn app() -> Result<App> {
+    // An empty configuration directory, the defaults only.
+    let config_dir = tempfile::tempdir()?;
+    let config = Config::load(config_dir.path())?;
+    Ok(App::with_config(config, 4.0, 60.0, false))
+}
+
+/// Run `app` on a `width` x `height` screen through `events`, followed by a render. Returns the
+/// last rendered screen.
+pub async fn run_app(app: &mut App, width: u16, height: u16, events: Vec<Event>) -> Result<Buffer> {
+    let script = ScriptedEvents::new(events.into_iter().chain([Event::Render, Event::Quit]));
+    let mut tui = Tui::with_backend(TestBackend::new(width, height), script)?;
+    app.run_with(&mut tui).await?;
+    Ok(tui.backend().buffer().clone())
+}
+
+/// Run an app through the session recorded in `path`, as fast as possible.
+pub async fn replay(width: u16, height: u16, path: &Path) -> Result<Buffer> {
+    let recording = recording::read(path)?;
+    let mut app = app()?;
+    app.replay_start(&recording.start)?;
+    let events = recording.events.into_iter().map(|timed| timed.event);
+    run_app(&mut app, width, height, events.collect()).await
+}
+
+/// The rows of `buffer` as text, without trailing spaces.
+pub fn screen(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let row: String = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Assert that the screen contains every one of `expected`, printing the screen otherwise.
+#[track_caller]
+pub fn assert_shows(buffer: &Buffer, expected: &[&str]) {
+    let screen = screen(buffer);
+    for text in expected {
+        assert!(screen.contains(text), "{text:?} not shown on\n{screen}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyCode;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{action::Action, recording::Start};
+
+    /// The screen after typing `input` and pressing `then`.
+    async fn show(input: &str, then: &str) -> Buffer {
+        let mut events = keys(input);
+        if !then.is_empty() {
+            events.extend(keys(then));
+        }
+        run(100, 30, events).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_home_shows_input_and_interpretations() {
+        let buffer = show("deadbeef", "").await;
+        assert_shows(&buffer, &["deadbeef", "Kind: u8", "222"]);
+    }
+
+    #[tokio::test]
+    async fn test_home_selectors_change_from_the_keyboard() {
+        let buffer = show("deadbeef", "<down><right><down><right>").await;
+        assert_shows(&buffer, &["Kind: i8", "Endianness: Big", "-34"]);
+    }
+
+    #[tokio::test]
+    async fn test_inspect_interprets_the_buffer() {
+        let buffer = show("deadbeef", "<F2>").await;
+        assert_shows(
+            &buffer,
+            &["u32        4022250974                                  3735928559"],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hex_view_dumps_the_buffer() {
+        let buffer = show("deadbeef", "<F3>").await;
+        assert_shows(&buffer, &["00000000  de ad be ef", "|....|"]);
+    }
+
+    #[tokio::test]
+    async fn test_bitwise_workbench() {
+        let buffer = show("deadbeef", "<F4>").await;
+        assert_shows(&buffer, &["11011110"]);
+    }
+
+    #[tokio::test]
+    async fn test_compare_mode() {
+        let buffer = show("deadbeef", "<F6><tab>").await;
+        assert_shows(&buffer, &["4 unmatched", "00000000  de ad be ef"]);
+        let mut events = keys("deadbeef");
+        events.extend(keys("<F6><tab>"));
+        events.extend(keys("deadbeee"));
+        let buffer = run(100, 30, events).await.unwrap();
+        assert_shows(
+            &buffer,
+            &["1 of 4 bytes differ, first at 0x3, Hamming distance 1"],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_popup() {
+        let buffer = show("deadbeef", "<x>").await;
+        assert_shows(&buffer, &["0xde, 0xad, 0xbe, 0xef"]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_reproduces_recorded_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let mut recorder = Recorder::create(&path, &Start::default()).unwrap();
+        for event in keys("deadbeef").iter().chain(&keys("<F3>")) {
+            recorder.record(event).unwrap();
+        }
+        drop(recorder);
+        let buffer = replay(100, 30, &path).await.unwrap();
+        assert_eq!(buffer, show("deadbeef", "<F3>").await);
+    }
+
+    #[tokio::test]
+    async fn test_replay_starts_with_the_recorded_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        // The file is gone where the recording is replayed.
+        let start = Start {
+            file: Some(dir.path().join("firmware.bin")),
+            input: "cafe".to_string(),
+            ..Start::default()
+        };
+        let mut recorder = Recorder::create(&path, &start).unwrap();
+        for event in keys("<F3>") {
+            recorder.record(&event).unwrap();
+        }
+        drop(recorder);
+        let buffer = replay(100, 30, &path).await.unwrap();
+        assert_shows(&buffer, &["00000000  ca fe"]);
+    }
+
+    #[tokio::test]
+    async fn test_paste_goes_to_the_palette_while_it_is_open() {
+        let mut events = keys("deadbeef");
+        events.extend(keys("<:>"));
+        events.push(Event::Paste("load cafe\n".to_string()));
+        let buffer = run(100, 30, events.clone()).await.unwrap();
+        assert_shows(&buffer, &["load cafe"]);
+
+        events.extend(keys("<esc>"));
+        let buffer = run(100, 30, events).await.unwrap();
+        assert_shows(&buffer, &["deadbeef"]);
+        assert!(!screen(&buffer).contains("cafe"));
+    }
+
+    #[tokio::test]
+    async fn test_edits_of_an_opened_file_are_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("firmware.bin");
+        std::fs::write(&path, [0xde, 0xad]).unwrap();
+        let mut app = app().unwrap();
+        app.open(&path).unwrap();
+        assert_eq!(app.session().file.as_deref(), Some(path.as_path()));
+
+        run_app(&mut app, 100, 30, keys("12")).await.unwrap();
+        let state = app.session();
+        assert_eq!(state.file, None);
+        assert_eq!(
+            state.actions()[0],
+            Action::SetBuffer(vec![0xde, 0xad, 0x12])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workspace_keeps_edits_of_an_opened_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("firmware.bin");
+        std::fs::write(&file, [0xde, 0xad]).unwrap();
+        let workspace = dir.path().join("firmware.json");
+        let mut events = keys("12");
+        events.extend(keys("<:>"));
+        let command = format!("save {}", workspace.display());
+        events.extend(command.chars().map(|c| Event::Key(KeyCode::Char(c).into())));
+        events.extend(keys("<enter>"));
+        let mut app = app().unwrap();
+        app.open(&file).unwrap();
+        run_app(&mut app, 100, 30, events).await.unwrap();
+
+        let saved = crate::workspace::load(&workspace).unwrap();
+        assert_eq!(saved.file, None);
+        assert_eq!(saved.input, "dead12");
+    }
+
+    #[tokio::test]
+    async fn test_checksums_popup() {
+        let buffer = show("deadbeef", "<s>").await;
+        assert_shows(&buffer, &["7c9ca35a"]);
+    }
+
+    #[tokio::test]
+    async fn test_workspace_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("firmware.json");
+        // The palette takes text as it is, unlike the bindings.
+        let command = |command: &str| -> Vec<Event> {
+            let text = format!("{command} {}", path.display());
+            keys("<:>")
+                .into_iter()
+                .chain(text.chars().map(|c| Event::Key(KeyCode::Char(c).into())))
+                .chain(keys("<enter>"))
+                .collect()
+        };
+        let mut events = keys("deadbeef");
+        events.extend(keys("<F3>"));
+        events.extend(command("save"));
+        run(100, 30, events).await.unwrap();
+
+        let buffer = run(100, 30, command("load")).await.unwrap();
+        assert_shows(&buffer, &["00000000  de ad be ef", "Loaded"]);
+    }
+}
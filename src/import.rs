@@ -0,0 +1,394 @@
+use crate::export::ElementWidth;
+
+/// Parse a byte buffer out of pasted text.
+///
+/// Understands the literals produced by [`crate::export::export`] (C, Rust, Python, Go and
+/// JavaScript), `xxd` and `hexdump -C` listings and plain hex streams as copied from Wireshark.
+/// Offsets and ASCII gutters of hex dumps are ignored.
+pub fn parse(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Nothing to import".to_string());
+    }
+    if let Some(bytes) = parse_byte_strings(text)? {
+        return Ok(bytes);
+    }
+    if let Some(offset_len) = offset_column(text) {
+        return parse_hex_dump(text, offset_len);
+    }
+    if text.contains(',') || text.contains('{') || text.contains('[') {
+        return parse_array(text);
+    }
+    parse_hex_stream(text)
+}
+
+/// Concatenate every `b"..."` or `b'...'` literal in `text`, `None` if there are none.
+fn parse_byte_strings(text: &str) -> Result<Option<Vec<u8>>, String> {
+    let mut bytes = Vec::new();
+    let mut found = false;
+    let mut rest = text;
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = rest.as_bytes()[start];
+        let prefixed = rest[..start].ends_with('b') || rest[..start].ends_with('B');
+        if !prefixed {
+            // A quote outside of a byte string, e.g. a C char literal.
+            if found {
+                return Err(format!("Unexpected `{}` in byte string", quote as char));
+            }
+            return Ok(None);
+        }
+        found = true;
+        let (literal, remaining) = unescape(&rest[start + 1..], quote)?;
+        bytes.extend(literal);
+        rest = remaining;
+    }
+    Ok(found.then_some(bytes))
+}
+
+/// Unescape a byte string up to the closing `quote`, returning the bytes and the text after it.
+fn unescape(text: &str, quote: u8) -> Result<(Vec<u8>, &str), String> {
+    let raw = text.as_bytes();
+    let mut bytes = Vec::new();
+    let mut i = 0;
+    while i < raw.len() {
+        match raw[i] {
+            b if b == quote => return Ok((bytes, &text[i + 1..])),
+            b'\\' => {
+                let Some(&escaped) = raw.get(i + 1) else {
+                    break;
+                };
+                i += 2;
+                match escaped {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'0' => bytes.push(0),
+                    b'\\' | b'\'' | b'"' => bytes.push(escaped),
+                    b'x' => {
+                        let hex = text.get(i..i + 2).ok_or("Truncated `\\x` escape")?;
+                        let byte = u8::from_str_radix(hex, 16)
+                            .map_err(|_| format!("Invalid escape `\\x{hex}`"))?;
+                        bytes.push(byte);
+                        i += 2;
+                    }
+                    b'\r' | b'\n' => {
+                        // Line continuation, skip the line break and leading whitespace.
+                        while i < raw.len() && raw[i].is_ascii_whitespace() {
+                            i += 1;
+                        }
+                    }
+                    other => return Err(format!("Unknown escape `\\{}`", other as char)),
+                }
+            }
+            b => {
+                bytes.push(b);
+                i += 1;
+            }
+        }
+    }
+    Err("Unterminated byte string".to_string())
+}
+
+/// Length of the leading offset column of a hex dump line, if the line has one. The offset ends
+/// with `:` in `xxd` listings and is followed by two spaces in `hexdump -C` ones.
+fn offset_len(line: &str) -> Option<usize> {
+    let len = loose_offset_len(line)?;
+    let rest = &line[len..];
+    (rest.starts_with(':') || rest.starts_with("  ")).then_some(len)
+}
+
+/// Length of the leading hex digits of a line followed by a separator, which may be an offset or
+/// merely the first group of a hex stream.
+fn loose_offset_len(line: &str) -> Option<usize> {
+    let len = line.find(|c: char| !c.is_ascii_hexdigit())?;
+    let separator = line[len..].chars().next()?;
+    (len >= 4 && (separator == ':' || separator == ' ')).then_some(len)
+}
+
+/// How the offset column of a hex dump is found, `None` if `text` is not a hex dump.
+///
+/// A first field only separated by a single space is an offset when there are several lines and
+/// their offsets increase by the same step, as a grouped hex stream looks alike otherwise.
+fn offset_column(text: &str) -> Option<fn(&str) -> Option<usize>> {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let is_dump = |offset: fn(&str) -> Option<usize>| {
+        lines
+            .iter()
+            .all(|line| offset(line).is_some() || is_bare_offset(line))
+            && lines.iter().any(|line| offset(line).is_some())
+    };
+    if is_dump(offset_len) {
+        return Some(offset_len);
+    }
+    if lines.len() < 2 || !is_dump(loose_offset_len) {
+        return None;
+    }
+    let offsets = lines
+        .iter()
+        .map(|line| {
+            let line = line.trim();
+            let len = loose_offset_len(line).unwrap_or(line.len());
+            u64::from_str_radix(&line[..len], 16).ok()
+        })
+        .collect::<Option<Vec<u64>>>()?;
+    let steps: Vec<u64> = offsets
+        .windows(2)
+        .map(|pair| pair[1].saturating_sub(pair[0]))
+        .collect();
+    // The last line may be shorter than the others.
+    let (last, full) = steps.split_last()?;
+    let step = full.first().unwrap_or(last);
+    let consistent = *step > 0 && full.iter().all(|s| s == step) && (1..=*step).contains(last);
+    consistent.then_some(loose_offset_len)
+}
+
+/// `hexdump -C` ends with a line holding only the total length.
+fn is_bare_offset(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 4 && line.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn parse_hex_dump(text: &str, offset_len: fn(&str) -> Option<usize>) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for line in text.lines() {
+        if line.trim() == "*" {
+            return Err("Hex dump contains a `*` line, re-run hexdump with `-v`".to_string());
+        }
+        let Some(offset) = offset_len(line) else {
+            continue;
+        };
+        let data = line[offset..].trim_start_matches(':');
+        // `hexdump -C` encloses the ASCII gutter in pipes, `xxd` separates it from the last
+        // group by two spaces.
+        let data = match data.find('|') {
+            Some(gutter) => &data[..gutter],
+            None => data.trim_start().split("  ").next().unwrap_or_default(),
+        };
+        bytes.extend(parse_hex_stream(data)?);
+    }
+    Ok(bytes)
+}
+
+fn parse_hex_stream(text: &str) -> Result<Vec<u8>, String> {
+    let digits: String = text
+        .split_whitespace()
+        .map(|token| token.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect::<String>()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-'))
+        .collect();
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("Unexpected character `{c}` in hex stream"));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("Hex stream has an odd number of digits".to_string());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Element width implied by a type such as `uint16_t`, `u32`, `[]uint64` or `Uint8Array`.
+fn declared_width(declaration: &str) -> ElementWidth {
+    let tokens = declaration
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|token| token.to_ascii_lowercase());
+    for token in tokens {
+        let token = token.strip_suffix("array").unwrap_or(&token);
+        let token = token.trim_start_matches("big");
+        let bits = token
+            .strip_prefix("uint")
+            .or_else(|| token.strip_prefix("int"))
+            .or_else(|| token.strip_prefix('u'))
+            .or_else(|| token.strip_prefix('i'));
+        match bits {
+            Some("16") => return ElementWidth::U16,
+            Some("32") => return ElementWidth::U32,
+            Some("64") => return ElementWidth::U64,
+            _ => {}
+        }
+    }
+    ElementWidth::U8
+}
+
+/// Parse a C, Rust, Go or JavaScript array initializer. Elements wider than a byte, as implied by
+/// the declared type, are stored little-endian.
+fn parse_array(text: &str) -> Result<Vec<u8>, String> {
+    // The initializer follows the `=` of the declaration, Go's `:=` included.
+    let initializer = text.find('=').map(|i| i + 1).unwrap_or(0);
+    // C and Go initializers use braces, Go's `[]uint16{` also has brackets in front of them.
+    let open = match text[initializer..].find('{') {
+        Some(brace) => initializer + brace,
+        None => initializer + text[initializer..].find('[').unwrap_or(0),
+    };
+    let close = text.rfind(['}', ']']).filter(|close| *close > open);
+    let body = &text[open..close.unwrap_or(text.len())];
+    let body = body.trim_start_matches(['{', '[']);
+    let width = declared_width(&text[..open]);
+
+    let mut bytes = Vec::new();
+    for element in body.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let value = parse_element(element)?;
+        if width.bytes() < 8 && value >> (width.bytes() * 8) != 0 {
+            return Err(format!("`{element}` does not fit into {width}"));
+        }
+        bytes.extend(&value.to_le_bytes()[..width.bytes()]);
+    }
+    Ok(bytes)
+}
+
+fn parse_element(element: &str) -> Result<u64, String> {
+    if let Some(c) = element
+        .strip_prefix('\'')
+        .and_then(|e| e.strip_suffix('\''))
+    {
+        let (bytes, _) = unescape(&format!("{c}'"), b'\'')?;
+        return match bytes[..] {
+            [b] => Ok(b.into()),
+            _ => Err(format!("Invalid character literal `{element}`")),
+        };
+    }
+    let lower = element.to_ascii_lowercase();
+    let digits = lower
+        .trim_end_matches(['u', 'l', 'n'])
+        .trim_end_matches("u8")
+        .trim_end_matches("u16")
+        .trim_end_matches("u32")
+        .trim_end_matches("u64")
+        .replace('_', "");
+    let parsed = if let Some(hex) = digits.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+    } else if let Some(bin) = digits.strip_prefix("0b") {
+        u64::from_str_radix(bin, 2)
+    } else if let Some(oct) = digits.strip_prefix("0o") {
+        u64::from_str_radix(oct, 8)
+    } else {
+        digits.parse()
+    };
+    parsed.map_err(|_| format!("Invalid array element `{element}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::export::{ExportConfig, LiteralFormat, export};
+    use crate::interpret::Endianness;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_round_trip_export() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for format in LiteralFormat::iter() {
+            for element_width in ElementWidth::iter() {
+                let config = ExportConfig {
+                    element_width,
+                    ..ExportConfig::default()
+                };
+                let literal = export(&bytes, format, &config);
+                assert_eq!(parse(&literal), Ok(bytes.clone()), "{literal}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_c_chars() {
+        assert_eq!(
+            parse("{ 'a', '\\n', 0x10, 7 }"),
+            Ok(vec![b'a', b'\n', 0x10, 7])
+        );
+    }
+
+    #[test]
+    fn test_parse_declared_width() {
+        assert_eq!(parse("uint8_t data[16] = { 1, 2 };"), Ok(vec![1, 2]));
+        assert_eq!(parse("let x: [u16; 1] = [0x0102];"), Ok(vec![2, 1]));
+        assert_eq!(parse("[]uint32{1}"), Ok(vec![1, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_parse_overflow() {
+        assert!(parse("{ 0x100 }").is_err());
+    }
+
+    #[test]
+    fn test_parse_xxd() {
+        let dump = "\
+00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 0001  Hello, world!...
+00000010: ff                                       .";
+        assert_eq!(parse(dump), Ok(b"Hello, world!\n\x00\x01\xff".to_vec()));
+    }
+
+    #[test]
+    fn test_parse_hexdump_canonical() {
+        let dump = "\
+00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 01  |Hello, world!...|
+00000010  ff                                                |.|
+00000011
+";
+        assert_eq!(parse(dump), Ok(b"Hello, world!\n\x00\x01\xff".to_vec()));
+    }
+
+    #[test]
+    fn test_parse_offsets_separated_by_a_space() {
+        // `hexdump` without `-C`, the offsets increase by the length of a line.
+        let dump = "\
+0000000 6548 6c6c 206f 6f77
+0000008 6c72 2164
+000000c
+";
+        assert_eq!(
+            parse(dump),
+            Ok(vec![
+                0x65, 0x48, 0x6c, 0x6c, 0x20, 0x6f, 0x6f, 0x77, 0x6c, 0x72, 0x21, 0x64
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_stream() {
+        assert_eq!(parse("deadbeef"), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(parse("de:ad be-ef"), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        // Groups are not offsets.
+        assert_eq!(
+            parse("deadbeef cafebabe"),
+            Ok(vec![0xde, 0xad, 0xbe, 0xef, 0xca, 0xfe, 0xba, 0xbe])
+        );
+        assert_eq!(
+            parse("deadbeef cafebabe\n12345678 9abcdef0").unwrap().len(),
+            16
+        );
+        assert!(parse("abc").is_err());
+        assert!(parse("hello").is_err());
+    }
+
+    proptest! {
+        /// Arrays of wider elements are read back little-endian and padded to whole elements.
+        #[test]
+        fn test_export_round_trip(
+            bytes in proptest::collection::vec(any::<u8>(), 0..40),
+            format in proptest::sample::select(LiteralFormat::iter().collect::<Vec<_>>()),
+            element_width in proptest::sample::select(ElementWidth::iter().collect::<Vec<_>>()),
+            endianness in proptest::sample::select(Endianness::iter().collect::<Vec<_>>()),
+            per_line in 0..16usize,
+        ) {
+            let config = ExportConfig { element_width, endianness, per_line, ..ExportConfig::default() };
+            let literal = export(&bytes, format, &config);
+            let mut expected = bytes.clone();
+            if !format.is_byte_string() {
+                let width = element_width.bytes();
+                expected.resize(bytes.len().div_ceil(width) * width, 0);
+                if endianness == Endianness::Big {
+                    expected.chunks_mut(width).for_each(<[u8]>::reverse);
+                }
+            }
+            prop_assert_eq!(parse(&literal), Ok(expected), "{}", literal);
+        }
+    }
+}
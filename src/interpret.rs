@@ -0,0 +1,469 @@
+//! Interpretation of byte buffers as numbers and text.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+/// Numeric types a buffer can be read as.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum Kind {
+    #[default]
+    #[strum(to_string = "u8")]
+    U8,
+    #[strum(to_string = "i8")]
+    I8,
+    #[strum(to_string = "u16")]
+    U16,
+    #[strum(to_string = "i16")]
+    I16,
+    #[strum(to_string = "u32")]
+    U32,
+    #[strum(to_string = "i32")]
+    I32,
+    #[strum(to_string = "u64")]
+    U64,
+    #[strum(to_string = "i64")]
+    I64,
+    #[strum(to_string = "u128")]
+    U128,
+    #[strum(to_string = "i128")]
+    I128,
+    #[strum(to_string = "f32")]
+    F32,
+    #[strum(to_string = "f64")]
+    F64,
+}
+
+/// Read a fixed size array from the start of `bytes`, in the given byte order.
+macro_rules! read {
+    ($ty:ty, $bytes:expr, $endianness:expr) => {{
+        let array = $bytes[..size_of::<$ty>()].try_into().ok()?;
+        match $endianness {
+            Endianness::Little => <$ty>::from_le_bytes(array),
+            Endianness::Big => <$ty>::from_be_bytes(array),
+        }
+    }};
+}
+
+impl Kind {
+    /// Size of the type in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            Kind::U8 | Kind::I8 => 1,
+            Kind::U16 | Kind::I16 => 2,
+            Kind::U32 | Kind::I32 | Kind::F32 => 4,
+            Kind::U64 | Kind::I64 | Kind::F64 => 8,
+            Kind::U128 | Kind::I128 => 16,
+        }
+    }
+
+    /// Decode the leading [`Kind::size`] bytes of `bytes`, `None` if the buffer is too short.
+    pub fn decode(&self, bytes: &[u8], endianness: Endianness) -> Option<String> {
+        if bytes.len() < self.size() {
+            return None;
+        }
+        let value = match self {
+            Kind::U8 => read!(u8, bytes, endianness).to_string(),
+            Kind::I8 => read!(i8, bytes, endianness).to_string(),
+            Kind::U16 => read!(u16, bytes, endianness).to_string(),
+            Kind::I16 => read!(i16, bytes, endianness).to_string(),
+            Kind::U32 => read!(u32, bytes, endianness).to_string(),
+            Kind::I32 => read!(i32, bytes, endianness).to_string(),
+            Kind::U64 => read!(u64, bytes, endianness).to_string(),
+            Kind::I64 => read!(i64, bytes, endianness).to_string(),
+            Kind::U128 => read!(u128, bytes, endianness).to_string(),
+            Kind::I128 => read!(i128, bytes, endianness).to_string(),
+            Kind::F32 => read!(f32, bytes, endianness).to_string(),
+            Kind::F64 => read!(f64, bytes, endianness).to_string(),
+        };
+        Some(value)
+    }
+}
+
+/// Text encodings a buffer can be read as.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, EnumIter, Serialize, Deserialize)]
+pub enum Encoding {
+    #[strum(to_string = "ASCII")]
+    Ascii,
+    #[strum(to_string = "Latin-1")]
+    Latin1,
+    #[default]
+    #[strum(to_string = "UTF-8")]
+    Utf8,
+    #[strum(to_string = "UTF-16LE")]
+    Utf16Le,
+    #[strum(to_string = "UTF-16BE")]
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Decode `bytes`, invalid sequences are replaced by U+FFFD.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Ascii => bytes
+                .iter()
+                .map(|b| {
+                    if b.is_ascii() {
+                        char::from(*b)
+                    } else {
+                        char::REPLACEMENT_CHARACTER
+                    }
+                })
+                .collect(),
+            Encoding::Latin1 => bytes.iter().map(|b| char::from(*b)).collect(),
+            Encoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let units = bytes.chunks_exact(2);
+                // A dangling byte can never form a valid code unit, nor complete a surrogate pair.
+                let dangling = !units.remainder().is_empty();
+                let units = units.map(|unit| match self {
+                    Encoding::Utf16Le => u16::from_le_bytes([unit[0], unit[1]]),
+                    _ => u16::from_be_bytes([unit[0], unit[1]]),
+                });
+                let mut text: String = char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect();
+                if dangling {
+                    text.push(char::REPLACEMENT_CHARACTER);
+                }
+                text
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    /// Every interpretation of the buffers of `corpus`, one per line. Kinds longer than a
+    /// buffer are left out.
+    fn report(corpus: &[(&str, Vec<u8>)]) -> String {
+        let mut report = String::new();
+        for (name, bytes) in corpus {
+            let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+            writeln!(report, "== {name}: {}", hex.join(" ")).unwrap();
+            for kind in Kind::iter() {
+                for endianness in Endianness::iter() {
+                    if let Some(value) = kind.decode(bytes, endianness) {
+                        writeln!(report, "{kind:<5} {endianness:<6} {value}").unwrap();
+                    }
+                }
+            }
+            for encoding in Encoding::iter() {
+                writeln!(report, "{encoding:<12} {:?}", encoding.decode(bytes)).unwrap();
+            }
+        }
+        report
+    }
+
+    /// Integer edge values, little endian and padded to 16 bytes so every kind decodes.
+    fn integer_corpus() -> Vec<(&'static str, Vec<u8>)> {
+        macro_rules! bounds {
+            ($($ty:ty),*) => {
+                vec![$(
+                    (concat!(stringify!($ty), "::MIN"), <$ty>::MIN.to_le_bytes().to_vec()),
+                    (concat!(stringify!($ty), "::MAX"), <$ty>::MAX.to_le_bytes().to_vec()),
+                )*]
+            };
+        }
+        let mut corpus = vec![("0", vec![0; 16]), ("-1", vec![0xff; 16])];
+        corpus.extend(bounds!(i8, i16, i32, i64, i128));
+        for (_, bytes) in &mut corpus {
+            bytes.resize(16, 0);
+        }
+        corpus
+    }
+
+    fn float_corpus() -> Vec<(&'static str, Vec<u8>)> {
+        let f32s = [
+            ("f32 0.0", 0.0f32),
+            ("f32 -0.0", -0.0),
+            ("f32::MIN", f32::MIN),
+            ("f32::MAX", f32::MAX),
+            ("f32::MIN_POSITIVE", f32::MIN_POSITIVE),
+            ("f32 smallest subnormal", f32::from_bits(1)),
+            ("f32 largest subnormal", f32::from_bits(0x007f_ffff)),
+            ("f32::INFINITY", f32::INFINITY),
+            ("f32::NEG_INFINITY", f32::NEG_INFINITY),
+            ("f32 quiet NaN", f32::from_bits(0x7fc0_0000)),
+            ("f32 NaN with payload", f32::from_bits(0x7fc0_0001)),
+            ("f32 signaling NaN", f32::from_bits(0x7f80_0001)),
+            ("f32 negative NaN", f32::from_bits(0xffff_ffff)),
+        ];
+        let f64s = [
+            ("f64 0.0", 0.0f64),
+            ("f64 -0.0", -0.0),
+            ("f64::MIN", f64::MIN),
+            ("f64::MAX", f64::MAX),
+            ("f64::MIN_POSITIVE", f64::MIN_POSITIVE),
+            ("f64 smallest subnormal", f64::from_bits(1)),
+            (
+                "f64 largest subnormal",
+                f64::from_bits(0x000f_ffff_ffff_ffff),
+            ),
+            ("f64::INFINITY", f64::INFINITY),
+            ("f64::NEG_INFINITY", f64::NEG_INFINITY),
+            ("f64 quiet NaN", f64::from_bits(0x7ff8_0000_0000_0000)),
+            (
+                "f64 NaN with payload",
+                f64::from_bits(0x7ff8_0000_0000_0001),
+            ),
+            ("f64 signaling NaN", f64::from_bits(0x7ff0_0000_0000_0001)),
+        ];
+        // From bits, the payload of a NaN survives.
+        let f32s = f32s.map(|(name, value)| (name, value.to_bits().to_le_bytes().to_vec()));
+        let f64s = f64s.map(|(name, value)| (name, value.to_bits().to_le_bytes().to_vec()));
+        f32s.into_iter().chain(f64s).collect()
+    }
+
+    fn text_corpus() -> Vec<(&'static str, Vec<u8>)> {
+        vec![
+            ("empty", vec![]),
+            ("control characters", b"\0\t\n\x1b\x7f".to_vec()),
+            ("lone continuation byte", vec![0x80]),
+            ("truncated sequence", vec![0xe2, 0x82]),
+            ("overlong slash", vec![0xc0, 0xaf]),
+            ("invalid byte", vec![0x61, 0xff, 0x62]),
+            ("UTF-8 encoded high surrogate", vec![0xed, 0xa0, 0x80]),
+            ("UTF-8 encoded low surrogate", vec![0xed, 0xb0, 0x80]),
+            ("beyond U+10FFFF", vec![0xf4, 0x90, 0x80, 0x80]),
+            ("U+1F600 in UTF-8", "😀".as_bytes().to_vec()),
+            ("U+1F600 in UTF-16LE", vec![0x3d, 0xd8, 0x00, 0xde]),
+            ("U+1F600 in UTF-16BE", vec![0xd8, 0x3d, 0xde, 0x00]),
+            (
+                "lone high surrogate in UTF-16LE",
+                vec![0x3d, 0xd8, 0x41, 0x00],
+            ),
+            (
+                "lone low surrogate in UTF-16BE",
+                vec![0xde, 0x00, 0x00, 0x41],
+            ),
+            ("odd length", vec![0x41, 0x00, 0x42]),
+            ("byte order mark", vec![0xef, 0xbb, 0xbf, 0xff, 0xfe]),
+        ]
+    }
+
+    #[test]
+    fn test_snapshot_integers() {
+        insta::assert_snapshot!(report(&integer_corpus()));
+    }
+
+    #[test]
+    fn test_snapshot_floats() {
+        insta::assert_snapshot!(report(&float_corpus()));
+    }
+
+    #[test]
+    fn test_snapshot_text() {
+        insta::assert_snapshot!(report(&text_corpus()));
+    }
+
+    #[test]
+    fn test_decode_kind() {
+        let bytes = [0xff, 0x00, 0x00, 0x80];
+        assert_eq!(Kind::U8.decode(&bytes, Endianness::Big), Some("255".into()));
+        assert_eq!(Kind::I8.decode(&bytes, Endianness::Big), Some("-1".into()));
+        assert_eq!(
+            Kind::I32.decode(&bytes, Endianness::Little),
+            Some("-2147483393".into())
+        );
+        assert_eq!(
+            Kind::U32.decode(&bytes, Endianness::Big),
+            Some("4278190208".into())
+        );
+        assert_eq!(Kind::U64.decode(&bytes, Endianness::Big), None);
+    }
+
+    #[test]
+    fn test_decode_float() {
+        let bytes = 1.5f32.to_be_bytes();
+        assert_eq!(
+            Kind::F32.decode(&bytes, Endianness::Big),
+            Some("1.5".into())
+        );
+    }
+
+    #[test]
+    fn test_decode_encoding() {
+        let bytes = b"h\xc3\xa9\xff";
+        assert_eq!(Encoding::Ascii.decode(bytes), "h\u{fffd}\u{fffd}\u{fffd}");
+        assert_eq!(Encoding::Latin1.decode(bytes), "hÃ©ÿ");
+        assert_eq!(Encoding::Utf8.decode(bytes), "hé\u{fffd}");
+        assert_eq!(Encoding::Utf16Le.decode(b"h\0i\0"), "hi");
+        assert_eq!(Encoding::Utf16Be.decode(b"\0h\0"), "h\u{fffd}");
+    }
+
+    #[test]
+    fn test_decode_utf16_dangling_byte_after_high_surrogate() {
+        // The dangling byte does not complete the surrogate pair.
+        assert_eq!(
+            Encoding::Utf16Le.decode(&[0x00, 0xd8, 0x00]),
+            "\u{fffd}\u{fffd}"
+        );
+        assert_eq!(
+            Encoding::Utf16Be.decode(&[0xd8, 0x00, 0xdc]),
+            "\u{fffd}\u{fffd}"
+        );
+    }
+
+    fn kind() -> impl Strategy<Value = Kind> {
+        proptest::sample::select(Kind::iter().collect::<Vec<_>>())
+    }
+
+    /// Floats round integers beyond their precision, they are tested apart.
+    fn integer_kind() -> impl Strategy<Value = Kind> {
+        kind().prop_filter("integer kind", |kind| {
+            !matches!(kind, Kind::F32 | Kind::F64)
+        })
+    }
+
+    fn endianness() -> impl Strategy<Value = Endianness> {
+        proptest::sample::select(Endianness::iter().collect::<Vec<_>>())
+    }
+
+    fn encoding() -> impl Strategy<Value = Encoding> {
+        proptest::sample::select(Encoding::iter().collect::<Vec<_>>())
+    }
+
+    /// Bytes of the size of a value of `kind`.
+    fn value_bytes() -> impl Strategy<Value = (Kind, Vec<u8>)> {
+        kind().prop_flat_map(|kind| {
+            (
+                Just(kind),
+                proptest::collection::vec(any::<u8>(), kind.size()),
+            )
+        })
+    }
+
+    // No value or text can be typed in, so the decoders are checked against reference encoders
+    // written with the standard library instead of round trips through a parser.
+
+    /// The bytes of a value written as [`Kind::decode`] writes it, `None` if it is no valid value
+    /// of the kind. NaN encodes to the canonical quiet NaN.
+    fn encode_value(kind: Kind, value: &str, endianness: Endianness) -> Option<Vec<u8>> {
+        macro_rules! write {
+            ($ty:ty) => {{
+                let value: $ty = value.parse().ok()?;
+                match endianness {
+                    Endianness::Little => value.to_le_bytes().to_vec(),
+                    Endianness::Big => value.to_be_bytes().to_vec(),
+                }
+            }};
+        }
+        let bytes = match kind {
+            Kind::U8 => write!(u8),
+            Kind::I8 => write!(i8),
+            Kind::U16 => write!(u16),
+            Kind::I16 => write!(i16),
+            Kind::U32 => write!(u32),
+            Kind::I32 => write!(i32),
+            Kind::U64 => write!(u64),
+            Kind::I64 => write!(i64),
+            Kind::U128 => write!(u128),
+            Kind::I128 => write!(i128),
+            Kind::F32 => write!(f32),
+            Kind::F64 => write!(f64),
+        };
+        Some(bytes)
+    }
+
+    /// The bytes of `text`, `None` if it has characters the encoding cannot represent.
+    fn encode_text(encoding: Encoding, text: &str) -> Option<Vec<u8>> {
+        match encoding {
+            Encoding::Ascii => text.is_ascii().then(|| text.as_bytes().to_vec()),
+            Encoding::Latin1 => text.chars().map(|c| u8::try_from(c).ok()).collect(),
+            Encoding::Utf8 => Some(text.as_bytes().to_vec()),
+            Encoding::Utf16Le => Some(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Encoding::Utf16Be => Some(text.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+        }
+    }
+
+    fn is_nan(kind: Kind, bytes: &[u8], endianness: Endianness) -> bool {
+        kind.decode(bytes, endianness).as_deref() == Some("NaN")
+    }
+
+    #[test]
+    fn test_nan_payloads_decode_alike() {
+        let payload = f32::from_bits(0xffc0_0001).to_le_bytes();
+        assert!(is_nan(Kind::F32, &payload, Endianness::Little));
+        assert_eq!(
+            encode_value(Kind::F32, "NaN", Endianness::Little),
+            Some(f32::NAN.to_le_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn test_unrepresentable_text() {
+        assert_eq!(encode_text(Encoding::Ascii, "é"), None);
+        assert_eq!(encode_text(Encoding::Latin1, "é"), Some(vec![0xe9]));
+        assert_eq!(encode_text(Encoding::Latin1, "€"), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_decoded_value_identifies_the_bytes((kind, bytes) in value_bytes(), endianness in endianness()) {
+            let value = kind.decode(&bytes, endianness).unwrap();
+            let encoded = encode_value(kind, &value, endianness);
+            if is_nan(kind, &bytes, endianness) {
+                let nan = encode_value(kind, "NaN", endianness);
+                prop_assert_eq!(encoded, nan);
+            } else {
+                prop_assert_eq!(encoded, Some(bytes), "{} {}", kind, value);
+            }
+        }
+
+        #[test]
+        fn test_integers_decode_canonically(value in any::<i128>(), kind in integer_kind(), endianness in endianness()) {
+            // Any integer the kind can hold, written the canonical way.
+            let value = value.to_string();
+            if let Some(bytes) = encode_value(kind, &value, endianness) {
+                prop_assert_eq!(kind.decode(&bytes, endianness), Some(value));
+            }
+        }
+
+        #[test]
+        fn test_floats_decode_to_the_shortest_form(single in any::<f32>(), double in any::<f64>(), endianness in endianness()) {
+            // Floats are written with the fewest digits that read back as the same value.
+            for (kind, value) in [(Kind::F32, single.to_string()), (Kind::F64, double.to_string())] {
+                let bytes = encode_value(kind, &value, endianness).unwrap();
+                prop_assert_eq!(kind.decode(&bytes, endianness), Some(value));
+            }
+        }
+
+        #[test]
+        fn test_encoded_text_decodes_back(text in "\\PC*", encoding in encoding()) {
+            if let Some(bytes) = encode_text(encoding, &text) {
+                prop_assert_eq!(encoding.decode(&bytes), text);
+            }
+        }
+
+        #[test]
+        fn test_decoded_text_identifies_valid_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..32), encoding in encoding()) {
+            let text = encoding.decode(&bytes);
+            let valid = match encoding {
+                Encoding::Ascii => bytes.is_ascii(),
+                Encoding::Latin1 => true,
+                Encoding::Utf8 => std::str::from_utf8(&bytes).is_ok(),
+                Encoding::Utf16Le | Encoding::Utf16Be => !text.contains(char::REPLACEMENT_CHARACTER),
+            };
+            if valid {
+                prop_assert_eq!(encode_text(encoding, &text), Some(bytes));
+            } else {
+                prop_assert!(text.contains(char::REPLACEMENT_CHARACTER));
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Matching of key presses against multi-key bindings.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::KeyEvent;
+
+use crate::{action::Action, app::Mode, config::Config};
+
+/// A [`KeyTrie`] of the bindings of every mode.
+pub fn keymaps(config: &Config) -> HashMap<Mode, KeyTrie> {
+    config
+        .keybindings
+        .iter()
+        .map(|(mode, bindings)| (*mode, KeyTrie::new(bindings)))
+        .collect()
+}
+
+/// Prefix tree of the key sequences bound in one mode.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KeyTrie {
+    action: Option<Action>,
+    children: HashMap<KeyEvent, KeyTrie>,
+}
+
+impl KeyTrie {
+    pub fn new(bindings: &HashMap<Vec<KeyEvent>, Action>) -> Self {
+        let mut trie = Self::default();
+        for (keys, action) in bindings {
+            let node = keys.iter().fold(&mut trie, |node, key| {
+                node.children.entry(*key).or_default()
+            });
+            node.action = Some(action.clone());
+        }
+        trie
+    }
+
+    /// The node reached by `keys`, `None` if no binding starts with them.
+    pub fn get(&self, keys: &[KeyEvent]) -> Option<&KeyTrie> {
+        keys.iter()
+            .try_fold(self, |node, key| node.children.get(key))
+    }
+
+    /// The action bound to the keys leading to this node.
+    pub fn action(&self) -> Option<&Action> {
+        self.action.as_ref()
+    }
+
+    /// The keys that continue the sequence leading to this node.
+    pub fn children(&self) -> impl Iterator<Item = (&KeyEvent, &KeyTrie)> {
+        self.children.iter()
+    }
+
+    /// Number of bindings below this node, this node included.
+    pub fn binding_count(&self) -> usize {
+        usize::from(self.action.is_some())
+            + self
+                .children
+                .values()
+                .map(KeyTrie::binding_count)
+                .sum::<usize>()
+    }
+}
+
+/// Collects key presses into chords.
+///
+/// A sequence that is the prefix of longer bindings stays pending until it is completed, broken
+/// by a key that continues no binding, or `timeout` passes without a key press. A pending
+/// sequence that is bound itself, e.g. `<g>` next to `<g><g>`, runs when it is broken or times
+/// out.
+#[derive(Debug, Clone)]
+pub struct ChordMatcher {
+    timeout: Duration,
+    pending: Vec<KeyEvent>,
+    last_press: Option<Instant>,
+}
+
+impl ChordMatcher {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            pending: Vec::new(),
+            last_press: None,
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Keys of the chord that is waiting for completion.
+    pub fn pending(&self) -> &[KeyEvent] {
+        &self.pending
+    }
+
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.last_press = None;
+    }
+
+    /// Add a key press at `now`, returning the actions of the chords it completes.
+    pub fn press(&mut self, trie: &KeyTrie, key: KeyEvent, now: Instant) -> Vec<Action> {
+        let mut actions: Vec<Action> = self.expire(trie, now).into_iter().collect();
+        self.pending.push(key);
+        self.last_press = Some(now);
+        match trie.get(&self.pending) {
+            Some(node) if node.children.is_empty() => {
+                actions.extend(node.action.clone());
+                self.reset();
+            }
+            // Wait for the next key.
+            Some(_) => {}
+            None if self.pending.len() > 1 => {
+                // The chord is broken, run what it has bound so far and start over with `key`.
+                let prefix = &self.pending[..self.pending.len() - 1];
+                actions.extend(trie.get(prefix).and_then(|node| node.action.clone()));
+                self.reset();
+                actions.extend(self.press(trie, key, now));
+            }
+            None => self.reset(),
+        }
+        actions
+    }
+
+    /// Give up on the pending chord if `timeout` passed since the last key press, returning the
+    /// action bound to the keys pressed so far.
+    pub fn expire(&mut self, trie: &KeyTrie, now: Instant) -> Option<Action> {
+        let last_press = self.last_press?;
+        if self.pending.is_empty() || now.duration_since(last_press) < self.timeout {
+            return None;
+        }
+        let action = trie.get(&self.pending).and_then(|node| node.action.clone());
+        self.reset();
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::config::parse_key_sequence;
+
+    fn trie(bindings: &[(&str, Action)]) -> KeyTrie {
+        let bindings = bindings
+            .iter()
+            .map(|(keys, action)| (parse_key_sequence(keys).unwrap(), action.clone()))
+            .collect();
+        KeyTrie::new(&bindings)
+    }
+
+    fn key(raw: &str) -> KeyEvent {
+        parse_key_sequence(raw).unwrap()[0]
+    }
+
+    #[test]
+    fn test_chord_completes_regardless_of_delay_below_timeout() {
+        let trie = trie(&[("<space><c><h>", Action::Help), ("<q>", Action::Quit)]);
+        let mut chords = ChordMatcher::new(Duration::from_secs(1));
+        let start = Instant::now();
+        assert_eq!(chords.press(&trie, key("<space>"), start), []);
+        let later = start + Duration::from_millis(900);
+        assert_eq!(chords.press(&trie, key("<c>"), later), []);
+        assert_eq!(chords.pending(), parse_key_sequence("<space><c>").unwrap());
+        let later = later + Duration::from_millis(900);
+        assert_eq!(chords.press(&trie, key("<h>"), later), [Action::Help]);
+        assert!(chords.pending().is_empty());
+    }
+
+    #[test]
+    fn test_timeout_drops_pending_chord() {
+        let trie = trie(&[("<g><g>", Action::GoTo(0))]);
+        let mut chords = ChordMatcher::new(Duration::from_secs(1));
+        let start = Instant::now();
+        chords.press(&trie, key("<g>"), start);
+        assert_eq!(
+            chords.expire(&trie, start + Duration::from_millis(500)),
+            None
+        );
+        assert_eq!(chords.expire(&trie, start + Duration::from_secs(1)), None);
+        assert!(chords.pending().is_empty());
+        let later = start + Duration::from_secs(2);
+        assert_eq!(chords.press(&trie, key("<g>"), later), []);
+    }
+
+    #[test]
+    fn test_ambiguous_prefix_runs_on_timeout_or_break() {
+        let trie = trie(&[
+            ("<g>", Action::Help),
+            ("<g><g>", Action::GoTo(0)),
+            ("<q>", Action::Quit),
+        ]);
+        let mut chords = ChordMatcher::new(Duration::from_secs(1));
+        let start = Instant::now();
+        assert_eq!(chords.press(&trie, key("<g>"), start), []);
+        assert_eq!(chords.press(&trie, key("<g>"), start), [Action::GoTo(0)]);
+
+        chords.press(&trie, key("<g>"), start);
+        let later = start + Duration::from_secs(1);
+        assert_eq!(chords.expire(&trie, later), Some(Action::Help));
+
+        chords.press(&trie, key("<g>"), later);
+        assert_eq!(
+            chords.press(&trie, key("<q>"), later),
+            [Action::Help, Action::Quit]
+        );
+    }
+
+    #[test]
+    fn test_broken_chord_restarts_with_last_key() {
+        let trie = trie(&[("<g><g>", Action::GoTo(0)), ("<x>", Action::ToggleExport)]);
+        let mut chords = ChordMatcher::new(Duration::from_secs(1));
+        let now = Instant::now();
+        chords.press(&trie, key("<g>"), now);
+        assert_eq!(chords.press(&trie, key("<x>"), now), [Action::ToggleExport]);
+        chords.press(&trie, key("<g>"), now);
+        assert_eq!(chords.press(&trie, key("<z>"), now), []);
+        assert!(chords.pending().is_empty());
+    }
+
+    #[test]
+    fn test_binding_count() {
+        let trie = trie(&[("<g>", Action::Help), ("<g><g>", Action::GoTo(0))]);
+        let g = trie.get(&[key("<g>")]).unwrap();
+        assert_eq!(g.binding_count(), 2);
+        assert_eq!(g.action(), Some(&Action::Help));
+    }
+}
@@ -0,0 +1,277 @@
+//! Arrangement of the panels of each [`Mode`] on the screen.
+
+use std::collections::HashMap;
+
+use derive_deref::{Deref, DerefMut};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::app::Mode;
+
+/// Identifies a component that can be placed in a layout.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Serialize, Deserialize,
+)]
+pub enum Panel {
+    Input,
+    Inspect,
+    Bits,
+    HexView,
+    Bitwise,
+    Compare,
+}
+
+/// Size of a node within its split, mirrors [`Constraint`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Size {
+    Length(u16),
+    Percentage(u16),
+    Ratio(u32, u32),
+    Min(u16),
+    Max(u16),
+    Fill(u16),
+}
+
+impl From<Size> for Constraint {
+    fn from(size: Size) -> Self {
+        match size {
+            Size::Length(length) => Constraint::Length(length),
+            Size::Percentage(percentage) => Constraint::Percentage(percentage),
+            Size::Ratio(numerator, denominator) => Constraint::Ratio(numerator, denominator),
+            Size::Min(min) => Constraint::Min(min),
+            Size::Max(max) => Constraint::Max(max),
+            Size::Fill(weight) => Constraint::Fill(weight),
+        }
+    }
+}
+
+/// A tree of splits whose leaves are panels.
+///
+/// In the configuration a panel is written as `{ "panel": "Inspect" }` and a split as
+/// `{ "split": "Horizontal", "children": [...] }`, both take an optional `"size"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawNode", into = "RawNode")]
+pub enum LayoutNode {
+    Panel {
+        panel: Panel,
+        /// Overrides the constraint requested by the panel's component.
+        size: Option<Size>,
+    },
+    Split {
+        split: Direction,
+        children: Vec<LayoutNode>,
+        /// Defaults to an equal share of the parent.
+        size: Option<Size>,
+    },
+}
+
+/// The configuration form of a [`LayoutNode`].
+///
+/// An untagged enum would be simpler, but the `config` crate lowercases keys and only matches
+/// enum variants case insensitively when it deserializes them itself.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    panel: Option<Panel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    split: Option<SplitDirection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<RawNode>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<Size>,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+impl TryFrom<RawNode> for LayoutNode {
+    type Error = String;
+
+    fn try_from(raw: RawNode) -> Result<Self, Self::Error> {
+        match (raw.panel, raw.split, raw.children) {
+            (Some(panel), None, None) => Ok(LayoutNode::Panel {
+                panel,
+                size: raw.size,
+            }),
+            (None, Some(split), Some(children)) => Ok(LayoutNode::Split {
+                split: match split {
+                    SplitDirection::Horizontal => Direction::Horizontal,
+                    SplitDirection::Vertical => Direction::Vertical,
+                },
+                children: children
+                    .into_iter()
+                    .map(LayoutNode::try_from)
+                    .collect::<Result<_, _>>()?,
+                size: raw.size,
+            }),
+            (Some(_), _, _) => Err("a panel can neither be split nor have children".to_string()),
+            _ => {
+                Err("a layout node needs either a `panel` or a `split` with `children`".to_string())
+            }
+        }
+    }
+}
+
+impl From<LayoutNode> for RawNode {
+    fn from(node: LayoutNode) -> Self {
+        match node {
+            LayoutNode::Panel { panel, size } => RawNode {
+                panel: Some(panel),
+                split: None,
+                children: None,
+                size,
+            },
+            LayoutNode::Split {
+                split,
+                children,
+                size,
+            } => RawNode {
+                panel: None,
+                split: Some(match split {
+                    Direction::Horizontal => SplitDirection::Horizontal,
+                    Direction::Vertical => SplitDirection::Vertical,
+                }),
+                children: Some(children.into_iter().map(RawNode::from).collect()),
+                size,
+            },
+        }
+    }
+}
+
+impl LayoutNode {
+    /// Every panel of the layout, in focus order.
+    pub fn panels(&self) -> Vec<Panel> {
+        match self {
+            LayoutNode::Panel { panel, .. } => vec![*panel],
+            LayoutNode::Split { children, .. } => {
+                children.iter().flat_map(LayoutNode::panels).collect()
+            }
+        }
+    }
+
+    fn constraint(&self, requested: &impl Fn(Panel) -> Constraint) -> Constraint {
+        match self {
+            LayoutNode::Panel { panel, size } => size.map_or_else(|| requested(*panel), Into::into),
+            LayoutNode::Split { size, .. } => size.map_or(Constraint::Fill(1), Into::into),
+        }
+    }
+
+    /// Split `area` into the areas of every panel. `requested` provides the constraint of a
+    /// panel without an explicit size.
+    pub fn areas(
+        &self,
+        area: Rect,
+        requested: &impl Fn(Panel) -> Constraint,
+    ) -> Vec<(Panel, Rect)> {
+        match self {
+            LayoutNode::Panel { panel, .. } => vec![(*panel, area)],
+            LayoutNode::Split {
+                split, children, ..
+            } => {
+                let constraints = children.iter().map(|child| child.constraint(requested));
+                let areas = Layout::new(*split, constraints).split(area);
+                children
+                    .iter()
+                    .zip(areas.iter())
+                    .flat_map(|(child, area)| child.areas(*area, requested))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The layout of every mode.
+#[derive(Clone, Debug, Default, Deref, DerefMut, Serialize, Deserialize)]
+pub struct Layouts(pub HashMap<Mode, LayoutNode>);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_areas() {
+        let layout: LayoutNode = json5::from_str(
+            r#"{
+                split: "Vertical",
+                children: [
+                    { panel: "Input", size: { Length: 4 } },
+                    { split: "Horizontal", children: [{ panel: "Inspect" }, { panel: "Bits" }] },
+                    { panel: "HexView", size: { Percentage: 50 } },
+                ],
+            }"#,
+        )
+        .unwrap();
+        let areas = layout.areas(Rect::new(0, 0, 80, 24), &|_| Constraint::Fill(1));
+        assert_eq!(
+            areas,
+            vec![
+                (Panel::Input, Rect::new(0, 0, 80, 4)),
+                (Panel::Inspect, Rect::new(0, 4, 40, 8)),
+                (Panel::Bits, Rect::new(40, 4, 40, 8)),
+                (Panel::HexView, Rect::new(0, 12, 80, 12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_requested_constraint() {
+        let layout: LayoutNode = json5::from_str(
+            r#"{ split: "Vertical", children: [{ panel: "Input" }, { panel: "Bits" }] }"#,
+        )
+        .unwrap();
+        let requested = |panel| match panel {
+            Panel::Input => Constraint::Length(3),
+            _ => Constraint::Fill(1),
+        };
+        let areas = layout.areas(Rect::new(0, 0, 10, 10), &requested);
+        assert_eq!(areas[0], (Panel::Input, Rect::new(0, 0, 10, 3)));
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        assert!(json5::from_str::<LayoutNode>(r#"{ panel: "Bits", sise: { Min: 1 } }"#).is_err());
+        assert!(json5::from_str::<LayoutNode>(r#"{ panel: "Header" }"#).is_err());
+        assert!(json5::from_str::<LayoutNode>(r#"{ split: "Vertical" }"#).is_err());
+    }
+
+    #[test]
+    fn test_config_layout_is_case_insensitive() {
+        // The `config` crate lowercases every key, including the variant names of `size`.
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"{ "split": "Horizontal", "size": { "Fill": 2 }, "children": [{ "panel": "Bits", "size": { "Ratio": [1, 3] } }] }"#,
+                config::FileFormat::Json5,
+            ))
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.try_deserialize::<LayoutNode>().unwrap(),
+            LayoutNode::Split {
+                split: Direction::Horizontal,
+                children: vec![LayoutNode::Panel {
+                    panel: Panel::Bits,
+                    size: Some(Size::Ratio(1, 3)),
+                }],
+                size: Some(Size::Fill(2)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_every_mode_has_a_default_layout() -> color_eyre::Result<()> {
+        let config = Config::new()?;
+        for mode in Mode::iter() {
+            assert!(!config.layouts[&mode].panels().is_empty(), "{mode}");
+        }
+        Ok(())
+    }
+}
@@ -1,36 +1,369 @@
+use std::{
+    collections::{BTreeSet, VecDeque},
+    ffi::OsString,
+    fmt::Write,
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::Instant,
+};
+
 use color_eyre::Result;
+use time::OffsetDateTime;
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use tracing_subscriber::{EnvFilter, Layer, fmt, layer::Context, prelude::*};
 
 use crate::config;
 
 lazy_static::lazy_static! {
     pub static ref LOG_ENV: String = format!("{}_LOG_LEVEL", config::PROJECT_NAME.clone());
-    pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+    /// The latest records of this process, shown by the log viewer.
+    pub static ref LOG_BUFFER: LogBuffer = LogBuffer::new(LOG_CAPACITY);
 }
 
-pub fn init() -> Result<()> {
-    let directory = config::get_data_dir();
-    std::fs::create_dir_all(directory.clone())?;
-    let log_path = directory.join(LOG_FILE.clone());
-    let log_file = std::fs::File::create(log_path)?;
-    let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
-    // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
-    // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
-    // errors, then this will return an error.
-    let env_filter = env_filter
-        .try_from_env()
-        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?;
+/// Number of records kept for the log viewer, older ones are dropped.
+const LOG_CAPACITY: usize = 1000;
+
+/// Number of session logs kept in the data directory, the current one included.
+const MAX_SESSION_LOGS: usize = 10;
+
+/// Size a session log grows to before it is moved aside to `<name>.old` and started afresh, so a
+/// session never takes more than twice as much.
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Where this process writes its log, set once logging is initialized.
+static LOG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// The log file of this session, `None` before logging is initialized.
+pub fn log_path() -> Option<&'static Path> {
+    LOG_PATH.get().map(PathBuf::as_path)
+}
+
+/// A log event as shown by the log viewer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    /// Seconds since the buffer was created.
+    pub seconds: f64,
+    pub level: Level,
+    pub target: String,
+    /// The message followed by the other fields as `name=value`.
+    pub message: String,
+}
+
+/// Bounded buffer of the latest records, shared between the tracing layer and the log viewer.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+    capacity: usize,
+    start: Instant,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+            start: Instant::now(),
+        }
+    }
+
+    fn push(&self, record: LogRecord) {
+        let Ok(mut records) = self.records.lock() else {
+            return;
+        };
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// The records at `level` or more severe, oldest first.
+    pub fn records(&self, level: Level) -> Vec<LogRecord> {
+        let Ok(records) = self.records.lock() else {
+            return Vec::new();
+        };
+        records
+            .iter()
+            .filter(|record| record.level <= level)
+            .cloned()
+            .collect()
+    }
+
+    /// A tracing layer adding every event to this buffer.
+    pub fn layer(&self) -> LogLayer {
+        LogLayer {
+            buffer: self.clone(),
+        }
+    }
+}
+
+pub struct LogLayer {
+    buffer: LogBuffer,
+}
+
+/// Collects the fields of an event into a single line.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        self.buffer.push(LogRecord {
+            seconds: self.buffer.start.elapsed().as_secs_f64(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message + &visitor.fields,
+        });
+    }
+}
+
+/// Log to `log_file`, or to a new file per session in the data directory of which only the
+/// latest [`MAX_SESSION_LOGS`] are kept.
+pub fn init(log_file: Option<&Path>) -> Result<()> {
+    let log_path = match log_file {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let directory = config::get_data_dir();
+            fs::create_dir_all(&directory)?;
+            remove_old_logs(&directory, MAX_SESSION_LOGS - 1)?;
+            directory.join(session_log_name(
+                OffsetDateTime::now_utc(),
+                std::process::id(),
+            ))
+        }
+    };
+    let log_file = CappedFile::open(&log_path, MAX_LOG_BYTES)?;
+    let _ = LOG_PATH.set(log_path);
     let file_subscriber = fmt::layer()
         .with_file(true)
         .with_line_number(true)
-        .with_writer(log_file)
+        .with_writer(Mutex::new(log_file))
         .with_target(false)
         .with_ansi(false)
-        .with_filter(env_filter);
+        .with_filter(env_filter()?);
+    let buffer_subscriber = LOG_BUFFER.layer().with_filter(env_filter()?);
     tracing_subscriber::registry()
         .with(file_subscriber)
+        .with(buffer_subscriber)
         .with(ErrorLayer::default())
         .try_init()?;
     Ok(())
 }
+
+/// File name of the log of a session started at `time` by process `pid`. The names sort in the
+/// order the sessions started.
+fn session_log_name(time: OffsetDateTime, pid: u32) -> String {
+    format!(
+        "{}-{:04}{:02}{:02}-{:02}{:02}{:02}-{pid}.log",
+        env!("CARGO_PKG_NAME"),
+        time.year(),
+        u8::from(time.month()),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+    )
+}
+
+/// Delete the session logs in `directory` but the latest `keep`, moved aside parts included.
+fn remove_old_logs(directory: &Path, keep: usize) -> io::Result<()> {
+    let prefix = format!("{}-", env!("CARGO_PKG_NAME"));
+    let mut sessions = BTreeSet::new();
+    for entry in fs::read_dir(directory)? {
+        let name = entry?.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let session = name.strip_suffix(".old").unwrap_or(name);
+        if session.starts_with(&prefix) && session.ends_with(".log") {
+            sessions.insert(session.to_string());
+        }
+    }
+    for session in sessions.iter().rev().skip(keep) {
+        let path = directory.join(session);
+        for path in [old_part(&path), path] {
+            match fs::remove_file(&path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Where a full log is moved to.
+fn old_part(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".old");
+    PathBuf::from(name)
+}
+
+/// A log file that is moved aside to [`old_part`] and started afresh once it would grow beyond
+/// `max_bytes`, replacing the part moved aside before.
+struct CappedFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    max_bytes: u64,
+}
+
+impl CappedFile {
+    /// Append to the file at `path`, creating it if needed.
+    fn open(path: &Path, max_bytes: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            len: file.metadata()?.len(),
+            file,
+            max_bytes,
+        })
+    }
+}
+
+impl io::Write for CappedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.len > 0 && self.len + buf.len() as u64 > self.max_bytes {
+            self.file.flush()?;
+            fs::rename(&self.path, old_part(&self.path))?;
+            self.file = File::create(&self.path)?;
+            self.len = 0;
+        }
+        let written = self.file.write(buf)?;
+        self.len += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn env_filter() -> Result<EnvFilter> {
+    let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
+    // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
+    // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
+    // errors, then this will return an error.
+    Ok(env_filter
+        .try_from_env()
+        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tracing::{debug, info, warn};
+
+    use super::*;
+
+    #[test]
+    fn test_buffer_keeps_latest_records() {
+        let buffer = LogBuffer::new(2);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            debug!("first");
+            info!(mode = "Home", "second");
+            warn!("third {}", 3);
+        });
+        let messages: Vec<_> = buffer
+            .records(Level::TRACE)
+            .into_iter()
+            .map(|record| (record.level, record.message))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (Level::INFO, "second mode=Home".to_string()),
+                (Level::WARN, "third 3".to_string()),
+            ]
+        );
+        assert_eq!(buffer.records(Level::WARN).len(), 1);
+    }
+
+    #[test]
+    fn test_session_log_name() {
+        let time = OffsetDateTime::from_unix_timestamp(1_760_000_000).unwrap();
+        assert_eq!(
+            session_log_name(time, 42),
+            format!("{}-20251009-085320-42.log", env!("CARGO_PKG_NAME"))
+        );
+    }
+
+    #[test]
+    fn test_remove_old_logs() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let name = |stamp: &str| format!("{}-{stamp}-1.log", env!("CARGO_PKG_NAME"));
+        for file in [
+            name("20250101-000000"),
+            name("20250101-000000") + ".old",
+            name("20250102-000000"),
+            name("20250103-000000"),
+            name("20250103-000000") + ".old",
+            "notes.txt".to_string(),
+        ] {
+            fs::write(dir.path().join(file), "")?;
+        }
+        remove_old_logs(dir.path(), 2)?;
+        let mut left: Vec<_> = fs::read_dir(dir.path())?
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                name("20250102-000000"),
+                name("20250103-000000"),
+                name("20250103-000000") + ".old",
+                "notes.txt".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_capped_file_moves_full_log_aside() -> Result<()> {
+        use std::io::Write as _;
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("session.log");
+        let mut file = CappedFile::open(&path, 10)?;
+        file.write_all(b"first\n")?;
+        file.write_all(b"second\n")?;
+        file.write_all(b"third\n")?;
+        assert_eq!(fs::read_to_string(&path)?, "third\n");
+        assert_eq!(fs::read_to_string(old_part(&path))?, "second\n");
+
+        // A later session appends to the same file.
+        let mut file = CappedFile::open(&path, 100)?;
+        file.write_all(b"fourth\n")?;
+        assert_eq!(fs::read_to_string(&path)?, "third\nfourth\n");
+        Ok(())
+    }
+}
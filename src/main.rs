@@ -1,26 +1,85 @@
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command, ConfigCommand};
 use color_eyre::Result;
 
-use crate::app::App;
+use crate::{
+    app::App,
+    config::{Config, config_files, get_config_dir},
+};
 
 mod action;
 mod app;
+mod bitops;
+mod checksum;
 mod cli;
 mod component;
 mod components;
 mod config;
+mod diff;
 mod errors;
+mod export;
+#[cfg(test)]
+mod harness;
+mod import;
+mod interpret;
+mod keymap;
+mod layout;
 mod logging;
+mod recording;
+mod recovery;
+mod theme;
 mod tui;
+mod workspace;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     crate::errors::init()?;
-    crate::logging::init()?;
-
     let args = Cli::parse();
-    let mut app = App::new(args.tick_rate, args.frame_rate)?;
-    app.run().await?;
+    crate::logging::init(args.log_file.as_deref())?;
+
+    if let Some(Command::Config {
+        command: ConfigCommand::Check,
+    }) = args.command
+    {
+        check_config()?;
+        return Ok(());
+    }
+    let mut app = App::new(args.tick_rate, args.frame_rate, args.mouse)?;
+    if let Some(file) = &args.file {
+        app.open(file)?;
+    }
+    if let Some(file) = &args.compare {
+        app.compare_with(file)?;
+    }
+    app.run(args.record.as_deref(), args.replay.as_deref())
+        .await?;
+    Ok(())
+}
+
+/// Print the configuration files that are read and every problem found in them, exits with an
+/// error status if there are any.
+fn check_config() -> Result<()> {
+    let config_dir = get_config_dir();
+    let files: Vec<_> = config_files(&config_dir)
+        .map(|(path, _)| path)
+        .filter(|path| path.exists())
+        .collect();
+    if files.is_empty() {
+        println!("No configuration file in {}", config_dir.display());
+    }
+    for file in &files {
+        println!("Reading {}", file.display());
+    }
+    let config = Config::load(&config_dir)?;
+    for diagnostic in &config.diagnostics {
+        println!("{diagnostic}");
+    }
+    match config.diagnostics.len() {
+        0 => println!("Configuration is valid"),
+        count => {
+            println!("{count} invalid entries, defaults are used instead");
+            std::process::exit(1);
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,256 @@
+//! Recording the events of a session to a file and replaying them, to reproduce a bug report.
+//!
+//! A recording has one JSON object per line. The first one holds what the session started with,
+//! which a replay applies before the events. Every other line holds an event and the milliseconds
+//! since the start of the session at which it arrived. Renders and ticks are not recorded, a
+//! replay draws and ticks at its own rate.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use color_eyre::{Result, eyre::WrapErr};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::mpsc::UnboundedSender,
+    task::JoinHandle,
+    time::{Duration, sleep_until},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    action::Action,
+    app::Mode,
+    components::home::parse_hex,
+    tui::{Event, EventSource, TerminalEvents},
+};
+
+/// The files a session started with and their bytes, so that it replays without them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Start {
+    /// The file given on the command line.
+    pub file: Option<PathBuf>,
+    /// The buffer as hex digits.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub input: String,
+    /// The file the buffer is compared with.
+    pub compare: Option<PathBuf>,
+    /// Its bytes as hex digits.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub compare_input: String,
+}
+
+impl Start {
+    /// The actions bringing a new session into this state.
+    pub fn actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        if self.file.is_some() || !self.input.is_empty() {
+            actions.push(Action::SetBuffer(parse_hex(&self.input)));
+        }
+        if self.compare.is_some() {
+            actions.push(Action::SetCompareBuffer(
+                parse_hex(&self.compare_input),
+                self.compare.clone(),
+            ));
+            actions.push(Action::SwitchMode(Mode::Compare));
+        }
+        actions
+    }
+}
+
+/// The first line of a recording.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    start: Start,
+}
+
+/// What a session started with and the events that followed.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    pub start: Start,
+    pub events: Vec<TimedEvent>,
+}
+
+/// An event and when it arrived.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedEvent {
+    pub millis: u64,
+    pub event: Event,
+}
+
+/// Appends the events of a session to a file as they arrive.
+#[derive(Debug)]
+pub struct Recorder {
+    file: LineWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, start: &Start) -> Result<Self> {
+        let file = File::create(path)
+            .wrap_err_with(|| format!("Cannot record the session to {}", path.display()))?;
+        let mut file = LineWriter::new(file);
+        let header = Header {
+            start: start.clone(),
+        };
+        serde_json::to_writer(&mut file, &header)?;
+        writeln!(file)?;
+        Ok(Self {
+            file,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, event: &Event) -> Result<()> {
+        if matches!(event, Event::Render | Event::Tick) {
+            return Ok(());
+        }
+        let timed = TimedEvent {
+            millis: self.start.elapsed().as_millis() as u64,
+            event: event.clone(),
+        };
+        serde_json::to_writer(&mut self.file, &timed)?;
+        // Every line is flushed on its own, a crash keeps the events leading to it.
+        writeln!(self.file)?;
+        Ok(())
+    }
+}
+
+/// Read a recording, its events in order. A recording without a first line of what the session
+/// started with starts empty.
+pub fn read(path: &Path) -> Result<Recording> {
+    let file = File::open(path)
+        .wrap_err_with(|| format!("Cannot open the recording {}", path.display()))?;
+    let mut recording = Recording::default();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if number == 0
+            && let Ok(header) = serde_json::from_str::<Header>(&line)
+        {
+            recording.start = header.start;
+            continue;
+        }
+        let event = serde_json::from_str(&line)
+            .wrap_err_with(|| format!("{}:{}: invalid event", path.display(), number + 1))?;
+        recording.events.push(event);
+    }
+    Ok(recording)
+}
+
+/// Sends the events of a recording at the times they were recorded, next to the events of the
+/// terminal. The terminal keeps working once the recording ends.
+#[derive(Debug, Clone)]
+pub struct ReplayEvents {
+    events: Vec<TimedEvent>,
+}
+
+impl ReplayEvents {
+    pub fn new(events: Vec<TimedEvent>) -> Self {
+        Self { events }
+    }
+}
+
+impl EventSource for ReplayEvents {
+    fn spawn(
+        &mut self,
+        event_tx: UnboundedSender<Event>,
+        cancellation_token: CancellationToken,
+        tick_rate: f64,
+        frame_rate: f64,
+    ) -> JoinHandle<()> {
+        let terminal = TerminalEvents.spawn(
+            event_tx.clone(),
+            cancellation_token.clone(),
+            tick_rate,
+            frame_rate,
+        );
+        // Resuming after a suspend must not replay the recording again.
+        let events = std::mem::take(&mut self.events);
+        tokio::spawn(async move {
+            let start = tokio::time::Instant::now();
+            let count = events.len();
+            for TimedEvent { millis, event } in events {
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => break,
+                    _ = sleep_until(start + Duration::from_millis(millis)) => {}
+                }
+                if event_tx.send(event).is_err() {
+                    warn!("Replay stopped, nothing receives the events");
+                    break;
+                }
+            }
+            info!("Replayed {count} events");
+            let _ = terminal.await;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_recording_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let start = Start {
+            file: Some(PathBuf::from("firmware.bin")),
+            input: "dead".to_string(),
+            ..Start::default()
+        };
+        let mut recorder = Recorder::create(&path, &start).unwrap();
+        let key = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        for event in [
+            Event::Init,
+            Event::Render,
+            Event::Tick,
+            Event::Key(key),
+            Event::Resize(80, 24),
+        ] {
+            recorder.record(&event).unwrap();
+        }
+        drop(recorder);
+
+        let recorded = read(&path).unwrap();
+        assert_eq!(recorded.start, start);
+        assert_eq!(
+            recorded.start.actions(),
+            [Action::SetBuffer(vec![0xde, 0xad])]
+        );
+        let events: Vec<Event> = recorded
+            .events
+            .iter()
+            .map(|timed| timed.event.clone())
+            .collect();
+        assert_eq!(
+            events,
+            [Event::Init, Event::Key(key), Event::Resize(80, 24)]
+        );
+        assert!(
+            recorded
+                .events
+                .windows(2)
+                .all(|w| w[0].millis <= w[1].millis)
+        );
+    }
+
+    #[test]
+    fn test_read_reports_invalid_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        std::fs::write(&path, "{\"millis\":0,\"event\":\"Init\"}\n\nnot json\n").unwrap();
+        let err = read(&path).unwrap_err();
+        assert!(err.to_string().ends_with("session.jsonl:3: invalid event"));
+    }
+}
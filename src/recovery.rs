@@ -0,0 +1,353 @@
+//! Saving the state of a session while it runs, to offer restoring it after a crash.
+//!
+//! The state is written to the data directory every few seconds while it changes, and once more
+//! by the panic hook. Quitting normally deletes it, so only an interrupted session is offered.
+
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    action::Action,
+    components::home::parse_hex,
+    interpret::{Encoding, Endianness, Kind},
+};
+
+/// Name of the saved state in the data directory.
+const RECOVERY_FILE: &str = "recovery.json";
+
+/// Time between two saves of a changing state.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The latest state of the running session, for the panic hook.
+static LATEST: Mutex<Option<(PathBuf, SessionState)>> = Mutex::new(None);
+
+/// What is needed to pick up an interrupted session.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    /// Seconds since the Unix epoch at which the state was saved.
+    pub saved_at: u64,
+    /// The buffer as hex digits, only if it does not match the file.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub input: String,
+    pub kind: Kind,
+    pub endianness: Endianness,
+    pub encoding: Encoding,
+    /// The file the buffer was read from, it is read again on restoring.
+    pub file: Option<PathBuf>,
+    /// Digest of the bytes read from `file`, to tell when the buffer was edited.
+    #[serde(skip)]
+    pub file_digest: u64,
+}
+
+impl SessionState {
+    /// The buffer is `bytes`, read from `file` if given.
+    pub fn set_file(&mut self, file: Option<PathBuf>, bytes: &[u8]) {
+        match file {
+            Some(_) => {
+                self.input.clear();
+                self.file_digest = digest(bytes);
+            }
+            None => self.input = to_hex(bytes),
+        }
+        self.file = file;
+    }
+
+    /// Track the state changes `action` makes, returns whether it made any.
+    pub fn update(&mut self, action: &Action) -> bool {
+        match action {
+            // The file is read again instead.
+            Action::SetBuffer(bytes)
+                if self.file.is_some() && digest(bytes) == self.file_digest =>
+            {
+                return false;
+            }
+            // Edited, the file no longer has the buffer.
+            Action::SetBuffer(bytes) => {
+                let input = to_hex(bytes);
+                if self.file.is_none() && input == self.input {
+                    return false;
+                }
+                self.file = None;
+                self.input = input;
+            }
+            Action::SetKind(kind) if *kind != self.kind => self.kind = *kind,
+            Action::SetEndianness(endianness) if *endianness != self.endianness => {
+                self.endianness = *endianness;
+            }
+            Action::SetEncoding(encoding) if *encoding != self.encoding => {
+                self.encoding = *encoding;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Whether there is anything worth restoring.
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty() && self.file.is_none()
+    }
+
+    /// The actions bringing the app into this state, but for reading the file if there is one.
+    pub fn actions(&self) -> Vec<Action> {
+        let buffer = match self.file {
+            Some(_) => None,
+            None => Some(Action::SetBuffer(parse_hex(&self.input))),
+        };
+        buffer
+            .into_iter()
+            .chain([
+                Action::SetKind(self.kind),
+                Action::SetEndianness(self.endianness),
+                Action::SetEncoding(self.encoding),
+            ])
+            .collect()
+    }
+
+    /// One line describing the state, for the restore prompt.
+    pub fn summary(&self) -> String {
+        let source = match &self.file {
+            Some(file) => file.display().to_string(),
+            None => format!("{} bytes", self.input.len() / 2),
+        };
+        format!(
+            "{source}, read as {} {} endian and {}",
+            self.kind, self.endianness, self.encoding
+        )
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn digest(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Saves the state of the running session to `path` and deletes it on a normal exit.
+#[derive(Debug)]
+pub struct Recovery {
+    path: PathBuf,
+    state: SessionState,
+    /// Whether the state changed since it was last saved.
+    dirty: bool,
+    last_save: Option<Instant>,
+}
+
+impl Recovery {
+    /// Track the session in `data_dir`, returning the state of an interrupted one if any.
+    pub fn start(data_dir: &Path) -> (Self, Option<SessionState>) {
+        let path = data_dir.join(RECOVERY_FILE);
+        let interrupted = load(&path).filter(|state| !state.is_empty());
+        let recovery = Self {
+            path,
+            state: SessionState::default(),
+            dirty: false,
+            last_save: None,
+        };
+        (recovery, interrupted)
+    }
+
+    /// The state of the session changed.
+    pub fn update(&mut self, state: &SessionState) {
+        self.state.clone_from(state);
+        self.dirty = true;
+        if let Ok(mut latest) = LATEST.lock() {
+            *latest = Some((self.path.clone(), self.state.clone()));
+        }
+    }
+
+    /// Save the state at `now` if it changed and the last save is long enough ago.
+    pub fn tick(&mut self, now: Instant) {
+        let due = self
+            .last_save
+            .is_none_or(|last_save| now.duration_since(last_save) >= SAVE_INTERVAL);
+        if !self.dirty || !due {
+            return;
+        }
+        if let Err(err) = save(&self.path, &self.state) {
+            warn!("Failed to save the session for recovery: {err}");
+        }
+        self.dirty = false;
+        self.last_save = Some(now);
+    }
+
+    /// Save the state now, the session is ending with an error.
+    pub fn flush(&mut self) {
+        if let Err(err) = save(&self.path, &self.state) {
+            warn!("Failed to save the session for recovery: {err}");
+        }
+        self.dirty = false;
+    }
+
+    /// Forget the session, it ended normally.
+    pub fn finish(self) {
+        if let Ok(mut latest) = LATEST.lock() {
+            *latest = None;
+        }
+        if let Err(err) = fs::remove_file(&self.path)
+            && err.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to delete {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// Save the latest state of the running session, called by the panic hook. Returns where it was
+/// saved, `None` if there is nothing to save.
+pub fn save_on_panic() -> Option<PathBuf> {
+    // The panic may have happened while the state was being updated.
+    let latest = LATEST.try_lock().ok()?;
+    let (path, state) = latest.as_ref()?;
+    save(path, state).ok()?;
+    Some(path.clone())
+}
+
+fn save(path: &Path, state: &SessionState) -> Result<()> {
+    let state = SessionState {
+        saved_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+        ..state.clone()
+    };
+    write_replacing(path, &serde_json::to_string_pretty(&state)?)
+}
+
+/// Write `contents` to `path`, creating its directory. Written next to the previous file and
+/// renamed, a crash while writing keeps that one.
+pub fn write_replacing(path: &Path, contents: &str) -> Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    fs::write(&partial, contents)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+fn load(path: &Path) -> Option<SessionState> {
+    let json = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&json) {
+        Ok(state) => Some(state),
+        Err(err) => {
+            warn!("Ignoring the unreadable session {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_state_is_saved_periodically_and_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut recovery, interrupted) = Recovery::start(dir.path());
+        assert_eq!(interrupted, None);
+
+        let start = Instant::now();
+        let mut state = SessionState::default();
+        for (action, now) in [
+            (Action::SetBuffer(vec![0xde, 0xad]), start),
+            (Action::SetKind(Kind::I16), start + Duration::from_secs(1)),
+            (
+                Action::SetEncoding(Encoding::Latin1),
+                start + Duration::from_secs(1),
+            ),
+        ] {
+            assert!(state.update(&action));
+            recovery.update(&state);
+            recovery.tick(now);
+        }
+        // The later changes came too soon after the first save.
+        let (_, interrupted) = Recovery::start(dir.path());
+        assert_eq!(interrupted.unwrap().kind, Kind::U8);
+
+        recovery.tick(start + SAVE_INTERVAL);
+        let (_, interrupted) = Recovery::start(dir.path());
+        let interrupted = interrupted.unwrap();
+        assert_eq!(
+            interrupted.actions(),
+            [
+                Action::SetBuffer(vec![0xde, 0xad]),
+                Action::SetKind(Kind::I16),
+                Action::SetEndianness(Endianness::Little),
+                Action::SetEncoding(Encoding::Latin1),
+            ]
+        );
+        assert_eq!(
+            interrupted.summary(),
+            "2 bytes, read as i16 Little endian and Latin-1"
+        );
+
+        recovery.finish();
+        let (_, interrupted) = Recovery::start(dir.path());
+        assert_eq!(interrupted, None);
+    }
+
+    #[test]
+    fn test_file_is_saved_by_path() {
+        let mut state = SessionState::default();
+        state.update(&Action::SetBuffer(vec![0xde, 0xad]));
+        state.set_file(Some(PathBuf::from("firmware.bin")), &[0xbe, 0xef]);
+        assert!(!state.update(&Action::SetBuffer(vec![0xbe, 0xef])));
+        assert_eq!(state.input, "");
+        // The file is read again instead of restoring a buffer.
+        assert_eq!(
+            state.actions(),
+            [
+                Action::SetKind(Kind::U8),
+                Action::SetEndianness(Endianness::Little),
+                Action::SetEncoding(Encoding::Utf8),
+            ]
+        );
+        assert_eq!(
+            state.summary(),
+            "firmware.bin, read as u8 Little endian and UTF-8"
+        );
+    }
+
+    #[test]
+    fn test_edited_file_is_saved_as_the_buffer() {
+        let mut state = SessionState::default();
+        state.set_file(Some(PathBuf::from("firmware.bin")), &[0xbe, 0xef]);
+        assert!(state.update(&Action::SetBuffer(vec![0xbe, 0xef, 0x0a])));
+        assert_eq!(state.file, None);
+        assert_eq!(
+            state.actions()[0],
+            Action::SetBuffer(vec![0xbe, 0xef, 0x0a])
+        );
+        // Undoing the edit does not bring the file back.
+        assert!(state.update(&Action::SetBuffer(vec![0xbe, 0xef])));
+        assert_eq!(
+            state.summary(),
+            "2 bytes, read as u8 Little endian and UTF-8"
+        );
+    }
+
+    #[test]
+    fn test_unreadable_or_empty_state_is_not_offered() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RECOVERY_FILE);
+        fs::write(&path, "{ not json").unwrap();
+        assert_eq!(Recovery::start(dir.path()).1, None);
+        save(&path, &SessionState::default()).unwrap();
+        assert_eq!(Recovery::start(dir.path()).1, None);
+    }
+}
@@ -0,0 +1,582 @@
+//! Styles of the semantic roles of the interface and the color grammar they are written in.
+
+use std::collections::HashMap;
+
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+/// What a style is used for, independent of the component drawing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Role {
+    /// The active tab of a header.
+    HeaderActive,
+    /// Border of the focused panel.
+    Focus,
+    /// The selected row or cell of a table.
+    Selected,
+    /// Error messages.
+    Error,
+    /// Bits that are set.
+    HighlightBit,
+    /// Bytes without a printable character.
+    InvalidByte,
+    /// Bytes and bits that differ between compared buffers.
+    Difference,
+}
+
+/// Colors the terminal can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorSupport {
+    /// True color if `COLORTERM` announces it, 256 colors otherwise.
+    #[default]
+    Auto,
+    TrueColor,
+    /// The 256 indexed colors, `#rrggbb` colors are replaced by the closest one.
+    Indexed,
+}
+
+impl ColorSupport {
+    fn true_color(self) -> bool {
+        match self {
+            ColorSupport::Auto => std::env::var("COLORTERM")
+                .is_ok_and(|value| value == "truecolor" || value == "24bit"),
+            ColorSupport::TrueColor => true,
+            ColorSupport::Indexed => false,
+        }
+    }
+}
+
+/// The `theme` section of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// One of the [`BUILTIN`] themes.
+    pub name: String,
+    /// Colors the styles can refer to by name, on top of the palette of the theme.
+    pub palette: HashMap<String, String>,
+    /// Styles of [`Role`]s, on top of the ones of the theme.
+    pub roles: HashMap<String, String>,
+    pub colors: ColorSupport,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            name: BUILTIN[0].name.to_string(),
+            palette: HashMap::new(),
+            roles: HashMap::new(),
+            colors: ColorSupport::default(),
+        }
+    }
+}
+
+/// A theme shipped with the application.
+struct Builtin {
+    name: &'static str,
+    palette: &'static [(&'static str, &'static str)],
+    roles: [(Role, &'static str); 7],
+}
+
+const BUILTIN: &[Builtin] = &[
+    Builtin {
+        name: "default",
+        palette: &[],
+        roles: [
+            (Role::HeaderActive, "inverse"),
+            (Role::Focus, "yellow"),
+            (Role::Selected, "inverse"),
+            (Role::Error, "red"),
+            (Role::HighlightBit, "bold"),
+            (Role::InvalidByte, "bright black"),
+            (Role::Difference, "bold red"),
+        ],
+    },
+    Builtin {
+        name: "solarized-dark",
+        palette: &[
+            ("base02", "#073642"),
+            ("base01", "#586e75"),
+            ("base1", "#93a1a1"),
+            ("yellow", "#b58900"),
+            ("orange", "#cb4b16"),
+            ("red", "#dc322f"),
+            ("blue", "#268bd2"),
+            ("cyan", "#2aa198"),
+        ],
+        roles: [
+            (Role::HeaderActive, "bold base1 on base02"),
+            (Role::Focus, "blue"),
+            (Role::Selected, "bold yellow on base02"),
+            (Role::Error, "bold red"),
+            (Role::HighlightBit, "bold cyan"),
+            (Role::InvalidByte, "base01"),
+            (Role::Difference, "bold orange"),
+        ],
+    },
+    Builtin {
+        name: "gruvbox",
+        palette: &[
+            ("bg1", "#3c3836"),
+            ("fg", "#ebdbb2"),
+            ("red", "#fb4934"),
+            ("yellow", "#fabd2f"),
+            ("aqua", "#8ec07c"),
+            ("orange", "#fe8019"),
+            ("gray", "#928374"),
+        ],
+        roles: [
+            (Role::HeaderActive, "bold fg on bg1"),
+            (Role::Focus, "orange"),
+            (Role::Selected, "bold yellow on bg1"),
+            (Role::Error, "bold red"),
+            (Role::HighlightBit, "bold aqua"),
+            (Role::InvalidByte, "gray"),
+            (Role::Difference, "bold red"),
+        ],
+    },
+    Builtin {
+        name: "high-contrast",
+        palette: &[],
+        roles: [
+            (Role::HeaderActive, "bold black on bright white"),
+            (Role::Focus, "bold bright white"),
+            (Role::Selected, "bold black on bright yellow"),
+            (Role::Error, "bold bright white on red"),
+            (Role::HighlightBit, "bold bright green"),
+            (Role::InvalidByte, "bold bright magenta"),
+            (Role::Difference, "bold bright white on red"),
+        ],
+    },
+];
+
+/// The resolved styles of every [`Role`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// What the theme was resolved from.
+    pub config: ThemeConfig,
+    /// Colors of the built-in theme and the configuration, by name.
+    palette: HashMap<String, Color>,
+    true_color: bool,
+    styles: HashMap<Role, Style>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new(ThemeConfig::default()).0
+    }
+}
+
+impl Theme {
+    /// Resolve `config` on top of its built-in theme. Entries that fail to parse are skipped and
+    /// reported.
+    pub fn new(config: ThemeConfig) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let builtin = BUILTIN
+            .iter()
+            .find(|builtin| builtin.name == config.name)
+            .unwrap_or_else(|| {
+                problems.push(format!(
+                    "unknown theme `{}`, expected one of {}",
+                    config.name,
+                    Theme::names().collect::<Vec<_>>().join(", ")
+                ));
+                &BUILTIN[0]
+            });
+
+        let mut palette = HashMap::new();
+        for (name, color) in builtin.palette {
+            palette.insert(
+                name.to_string(),
+                parse_color(color, &HashMap::new()).unwrap(),
+            );
+        }
+        let mut entries: Vec<_> = config.palette.iter().collect();
+        entries.sort();
+        for (name, color) in entries {
+            match parse_color(color, &HashMap::new()) {
+                Some(color) => {
+                    palette.insert(name.to_lowercase(), color);
+                }
+                None => problems.push(format!("unknown color `{color}` in palette entry `{name}`")),
+            }
+        }
+
+        let mut styles: HashMap<Role, Style> = builtin
+            .roles
+            .iter()
+            .map(|(role, style)| (*role, try_parse_style(style, &palette).unwrap()))
+            .collect();
+        let mut entries: Vec<_> = config.roles.iter().collect();
+        entries.sort();
+        for (name, style) in entries {
+            let Some(role) = Role::iter().find(|role| role.to_string() == *name) else {
+                problems.push(format!("unknown role `{name}`"));
+                continue;
+            };
+            match try_parse_style(style, &palette) {
+                Ok(style) => {
+                    styles.insert(role, style);
+                }
+                Err(err) => problems.push(format!("role `{name}`: {err}")),
+            }
+        }
+
+        let mut theme = Self {
+            true_color: config.colors.true_color(),
+            config,
+            palette,
+            styles: HashMap::new(),
+        };
+        theme.styles = styles
+            .into_iter()
+            .map(|(role, style)| (role, theme.adapt(style)))
+            .collect();
+        (theme, problems)
+    }
+
+    /// Parse a style like the roles are, colors may be names of the palette.
+    pub fn parse_style(&self, line: &str) -> Result<Style, String> {
+        try_parse_style(line, &self.palette).map(|style| self.adapt(style))
+    }
+
+    /// `style` with its RGB colors replaced by indexed ones if the terminal lacks true color.
+    pub fn adapt(&self, mut style: Style) -> Style {
+        if !self.true_color {
+            style.fg = style.fg.map(indexed);
+            style.bg = style.bg.map(indexed);
+        }
+        style
+    }
+
+    /// Names of the built-in themes.
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        BUILTIN.iter().map(|builtin| builtin.name)
+    }
+
+    pub fn style(&self, role: Role) -> Style {
+        self.styles.get(&role).copied().unwrap_or_default()
+    }
+}
+
+/// The closest of the 256 indexed colors to an RGB color, other colors are kept.
+fn indexed(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    // Channel levels of the 6x6x6 color cube starting at index 16.
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |value: u8| {
+        (0..LEVELS.len())
+            .min_by_key(|i| LEVELS[*i].abs_diff(value))
+            .unwrap_or_default()
+    };
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .into_iter()
+            .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+            .sum::<u32>()
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+    let cube_distance = distance((LEVELS[ri], LEVELS[gi], LEVELS[bi]));
+    // The gray ramp from index 232 has levels 8, 18, ..., 238.
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * step;
+    if distance((gray, gray, gray)) < cube_distance {
+        Color::Indexed(232 + step)
+    } else {
+        Color::Indexed(cube)
+    }
+}
+
+/// Parse a style of the form `[bold] [underline] [inverse] [color] [on color]`, colors that are
+/// not understood are ignored.
+fn parse_style(line: &str, palette: &HashMap<String, Color>) -> Style {
+    let (foreground, background) =
+        line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
+    let foreground = process_color_string(foreground);
+    let background = process_color_string(&background.replace("on ", ""));
+
+    let mut style = Style::default();
+    if let Some(fg) = parse_color(&foreground.0, palette) {
+        style = style.fg(fg);
+    }
+    if let Some(bg) = parse_color(&background.0, palette) {
+        style = style.bg(bg);
+    }
+    style = style.add_modifier(foreground.1 | background.1);
+    style
+}
+
+/// Like [`parse_style`], but fails on colors that are not understood instead of ignoring them.
+/// Colors may be names of `palette`.
+pub fn try_parse_style(line: &str, palette: &HashMap<String, Color>) -> Result<Style, String> {
+    let (foreground, background) =
+        line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
+    for part in [foreground, &background.replace("on ", "")] {
+        let (color, _) = process_color_string(part);
+        let color = color.trim();
+        if !color.is_empty() && parse_color(color, palette).is_none() {
+            return Err(format!("unknown color `{color}` in style `{line}`"));
+        }
+    }
+    Ok(parse_style(line, palette))
+}
+
+fn process_color_string(color_str: &str) -> (String, Modifier) {
+    let color = color_str
+        .replace("grey", "gray")
+        .replace("bold ", "")
+        .replace("underline ", "")
+        .replace("inverse ", "");
+
+    let mut modifiers = Modifier::empty();
+    if color_str.contains("underline") {
+        modifiers |= Modifier::UNDERLINED;
+    }
+    if color_str.contains("bold") {
+        modifiers |= Modifier::BOLD;
+    }
+    if color_str.contains("inverse") {
+        modifiers |= Modifier::REVERSED;
+    }
+
+    let color = match color.trim() {
+        "bold" | "underline" | "inverse" => String::new(),
+        _ => color,
+    };
+    (color, modifiers)
+}
+
+/// A name of `palette`, `#rrggbb`, `colorN`, `grayN` (0 to 23), `rgbRGB` (each 0 to 5), one of
+/// the 8 basic color names, or `bright` followed by a basic color even if the palette has a color
+/// of that name.
+fn parse_color(s: &str, palette: &HashMap<String, Color>) -> Option<Color> {
+    let s = s.trim().to_lowercase();
+    if let Some(color) = s.strip_prefix("bright ") {
+        // The bright variants of the basic colors follow them in the indexed colors. A palette
+        // may name its own `red`, which has no bright variant.
+        return match parse_color(color, &HashMap::new())? {
+            Color::Indexed(c) if c < 8 => Some(Color::Indexed(c + 8)),
+            _ => None,
+        };
+    }
+    if let Some(color) = palette.get(&s) {
+        return Some(*color);
+    }
+    let number = |prefix: &str| s.strip_prefix(prefix).and_then(|n| n.parse::<u8>().ok());
+    if let Some(hex) = s.strip_prefix('#') {
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        if hex.len() != 6 {
+            return None;
+        }
+        Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+    } else if let Some(c) = number("color") {
+        Some(Color::Indexed(c))
+    } else if let Some(c) = number("gray").filter(|c| *c < 24) {
+        Some(Color::Indexed(232 + c))
+    } else if let Some(rgb) = s.strip_prefix("rgb") {
+        let levels: Vec<u8> = rgb
+            .chars()
+            .map(|c| c.to_digit(6).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        let [red, green, blue] = levels[..] else {
+            return None;
+        };
+        Some(Color::Indexed(16 + red * 36 + green * 6 + blue))
+    } else {
+        let index = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ]
+        .iter()
+        .position(|name| *name == s)?;
+        Some(Color::Indexed(index as u8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Stylize;
+
+    use super::*;
+
+    fn parse(line: &str) -> Style {
+        parse_style(line, &HashMap::new())
+    }
+
+    #[test]
+    fn test_parse_style_default() {
+        let style = parse("");
+        assert_eq!(style, Style::default());
+    }
+
+    #[test]
+    fn test_parse_style_foreground() {
+        let style = parse("red");
+        assert_eq!(style.fg, Some(Color::Indexed(1)));
+    }
+
+    #[test]
+    fn test_parse_style_background() {
+        let style = parse("on blue");
+        assert_eq!(style.bg, Some(Color::Indexed(4)));
+    }
+
+    #[test]
+    fn test_parse_style_modifiers() {
+        let style = parse("underline red on blue");
+        assert_eq!(style.fg, Some(Color::Indexed(1)));
+        assert_eq!(style.bg, Some(Color::Indexed(4)));
+        assert_eq!(parse("bold"), Style::new().bold());
+    }
+
+    #[test]
+    fn test_process_color_string() {
+        let (color, modifiers) = process_color_string("underline bold inverse gray");
+        assert_eq!(color, "gray");
+        assert!(modifiers.contains(Modifier::UNDERLINED));
+        assert!(modifiers.contains(Modifier::BOLD));
+        assert!(modifiers.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_parse_color_rgb() {
+        let color = parse_color("rgb123", &HashMap::new());
+        let expected = 16 + 36 + 2 * 6 + 3;
+        assert_eq!(color, Some(Color::Indexed(expected)));
+        assert_eq!(parse_color("rgb", &HashMap::new()), None);
+        assert_eq!(parse_color("rgb678", &HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_parse_color_unknown() {
+        let color = parse_color("unknown", &HashMap::new());
+        assert_eq!(color, None);
+    }
+
+    #[test]
+    fn test_parse_color_bright_and_hex() {
+        let palette = HashMap::new();
+        assert_eq!(parse_color("bright red", &palette), Some(Color::Indexed(9)));
+        assert_eq!(
+            parse_color("bright color3", &palette),
+            Some(Color::Indexed(11))
+        );
+        assert_eq!(parse_color("bright color9", &palette), None);
+        assert_eq!(parse("bright blue").fg, Some(Color::Indexed(12)));
+        assert_eq!(
+            parse_color("#FF8000", &palette),
+            Some(Color::Rgb(255, 128, 0))
+        );
+        assert_eq!(parse_color("#ff80", &palette), None);
+        assert_eq!(parse_color("#gg8000", &palette), None);
+    }
+
+    #[test]
+    fn test_try_parse_style() {
+        let palette = HashMap::from([("accent".to_string(), Color::Rgb(1, 2, 3))]);
+        assert_eq!(
+            try_parse_style("bold red on blue", &palette),
+            Ok(parse("bold red on blue"))
+        );
+        assert_eq!(
+            try_parse_style("accent on red", &palette),
+            Ok(Style::new().fg(Color::Rgb(1, 2, 3)).bg(Color::Indexed(1)))
+        );
+        assert!(try_parse_style("bluish", &palette).is_err());
+        assert!(try_parse_style("red on nothing", &palette).is_err());
+    }
+
+    #[test]
+    fn test_builtin_themes_parse() {
+        for name in Theme::names() {
+            let config = ThemeConfig {
+                name: name.to_string(),
+                colors: ColorSupport::TrueColor,
+                ..ThemeConfig::default()
+            };
+            let (theme, problems) = Theme::new(config);
+            assert_eq!(problems, Vec::<String>::new(), "{name}");
+            assert_ne!(theme.style(Role::Error), Style::default(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_theme_overrides_and_problems() {
+        let config = ThemeConfig {
+            name: "gruvbox".to_string(),
+            palette: HashMap::from([
+                ("accent".to_string(), "#112233".to_string()),
+                ("broken".to_string(), "#12".to_string()),
+            ]),
+            roles: HashMap::from([
+                ("error".to_string(), "underline accent".to_string()),
+                ("highlight-bit".to_string(), "bluish".to_string()),
+                ("sparkle".to_string(), "red".to_string()),
+            ]),
+            colors: ColorSupport::TrueColor,
+        };
+        let (theme, problems) = Theme::new(config);
+        assert_eq!(
+            theme.style(Role::Error),
+            Style::new().fg(Color::Rgb(0x11, 0x22, 0x33)).underlined()
+        );
+        assert_eq!(
+            theme.style(Role::HighlightBit),
+            Style::new().fg(Color::Rgb(0x8e, 0xc0, 0x7c)).bold()
+        );
+        assert_eq!(problems.len(), 3);
+    }
+
+    #[test]
+    fn test_bright_colors_ignore_the_palette() {
+        let config = ThemeConfig {
+            name: "gruvbox".to_string(),
+            roles: HashMap::from([("error".to_string(), "bright red on red".to_string())]),
+            colors: ColorSupport::TrueColor,
+            ..ThemeConfig::default()
+        };
+        let (theme, problems) = Theme::new(config);
+        assert_eq!(problems, Vec::<String>::new());
+        assert_eq!(
+            theme.style(Role::Error),
+            Style::new()
+                .fg(Color::Indexed(9))
+                .bg(Color::Rgb(0xfb, 0x49, 0x34))
+        );
+    }
+
+    #[test]
+    fn test_unknown_theme_falls_back_to_default() {
+        let config = ThemeConfig {
+            name: "neon".to_string(),
+            ..ThemeConfig::default()
+        };
+        let (theme, problems) = Theme::new(config);
+        assert_eq!(theme.style(Role::Focus), parse("yellow"));
+        assert_eq!(problems.len(), 1);
+    }
+
+    #[test]
+    fn test_indexed_fallback() {
+        assert_eq!(indexed(Color::Rgb(0, 0, 0)), Color::Indexed(16));
+        assert_eq!(indexed(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(indexed(Color::Rgb(0x80, 0x80, 0x80)), Color::Indexed(244));
+        assert_eq!(indexed(Color::Indexed(3)), Color::Indexed(3));
+        let config = ThemeConfig {
+            name: "solarized-dark".to_string(),
+            colors: ColorSupport::Indexed,
+            ..ThemeConfig::default()
+        };
+        let (theme, _) = Theme::new(config);
+        assert!(matches!(
+            theme.style(Role::Error).fg,
+            Some(Color::Indexed(_))
+        ));
+    }
+}
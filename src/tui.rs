@@ -16,7 +16,7 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
-use ratatui::backend::CrosstermBackend as Backend;
+use ratatui::backend::{Backend, CrosstermBackend, TestBackend};
 use serde::{Deserialize, Serialize};
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
@@ -26,7 +26,9 @@ use tokio::{
 use tokio_util::sync::CancellationToken;
 use tracing::error;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use crate::recording::Recorder;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     Init,
     Quit,
@@ -42,8 +44,123 @@ pub enum Event {
     Resize(u16, u16),
 }
 
-pub struct Tui {
-    pub terminal: ratatui::Terminal<Backend<Stdout>>,
+/// Produces the events of a [`Tui`] until it is cancelled.
+pub trait EventSource: Send {
+    /// Spawn a task that sends events to `event_tx` until `cancellation_token` is cancelled.
+    fn spawn(
+        &mut self,
+        event_tx: UnboundedSender<Event>,
+        cancellation_token: CancellationToken,
+        tick_rate: f64,
+        frame_rate: f64,
+    ) -> JoinHandle<()>;
+}
+
+/// Input of the terminal, interleaved with ticks and renders at their rates.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn spawn(
+        &mut self,
+        event_tx: UnboundedSender<Event>,
+        cancellation_token: CancellationToken,
+        tick_rate: f64,
+        frame_rate: f64,
+    ) -> JoinHandle<()> {
+        tokio::spawn(event_loop(
+            event_tx,
+            cancellation_token,
+            tick_rate,
+            frame_rate,
+        ))
+    }
+}
+
+/// A fixed sequence of events, sent all at once without ticks or renders in between.
+#[derive(Debug, Default, Clone)]
+pub struct ScriptedEvents {
+    events: Vec<Event>,
+}
+
+impl ScriptedEvents {
+    pub fn new(events: impl IntoIterator<Item = Event>) -> Self {
+        Self {
+            events: events.into_iter().collect(),
+        }
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn spawn(
+        &mut self,
+        event_tx: UnboundedSender<Event>,
+        _cancellation_token: CancellationToken,
+        _tick_rate: f64,
+        _frame_rate: f64,
+    ) -> JoinHandle<()> {
+        // Resuming after a suspend must not replay the script.
+        let events = std::mem::take(&mut self.events);
+        tokio::spawn(async move {
+            for event in events {
+                if event_tx.send(event).is_err() {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+/// Preparing the terminal behind a backend for a [`Tui`] and restoring it afterwards.
+pub trait TerminalMode {
+    fn enter_mode(&mut self, mouse: bool, paste: bool) -> Result<()>;
+
+    fn exit_mode(&mut self, mouse: bool, paste: bool) -> Result<()>;
+}
+
+impl TerminalMode for CrosstermBackend<Stdout> {
+    fn enter_mode(&mut self, mouse: bool, paste: bool) -> Result<()> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(stdout(), EnterAlternateScreen, cursor::Hide)?;
+        if mouse {
+            crossterm::execute!(stdout(), EnableMouseCapture)?;
+        }
+        if paste {
+            crossterm::execute!(stdout(), EnableBracketedPaste)?;
+        }
+        Ok(())
+    }
+
+    fn exit_mode(&mut self, mouse: bool, paste: bool) -> Result<()> {
+        if crossterm::terminal::is_raw_mode_enabled()? {
+            self.flush()?;
+            if paste {
+                crossterm::execute!(stdout(), DisableBracketedPaste)?;
+            }
+            if mouse {
+                crossterm::execute!(stdout(), DisableMouseCapture)?;
+            }
+            crossterm::execute!(stdout(), LeaveAlternateScreen, cursor::Show)?;
+            crossterm::terminal::disable_raw_mode()?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders into a buffer only, there is no terminal to prepare.
+impl TerminalMode for TestBackend {
+    fn enter_mode(&mut self, _mouse: bool, _paste: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn exit_mode(&mut self, _mouse: bool, _paste: bool) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Tui<B: Backend + TerminalMode = CrosstermBackend<Stdout>> {
+    pub terminal: ratatui::Terminal<B>,
+    pub events: Box<dyn EventSource>,
     pub task: JoinHandle<()>,
     pub cancellation_token: CancellationToken,
     pub event_rx: UnboundedReceiver<Event>,
@@ -52,13 +169,23 @@ pub struct Tui {
     pub tick_rate: f64,
     pub mouse: bool,
     pub paste: bool,
+    /// Writes every event that is received, if the session is recorded.
+    pub recorder: Option<Recorder>,
 }
 
 impl Tui {
+    /// Draw on the standard output and read the events of the terminal.
     pub fn new() -> Result<Self> {
+        Self::with_backend(CrosstermBackend::new(stdout()), TerminalEvents)
+    }
+}
+
+impl<B: Backend + TerminalMode> Tui<B> {
+    pub fn with_backend(backend: B, events: impl EventSource + 'static) -> Result<Self> {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         Ok(Self {
-            terminal: ratatui::Terminal::new(Backend::new(stdout()))?,
+            terminal: ratatui::Terminal::new(backend)?,
+            events: Box::new(events),
             task: tokio::spawn(async {}),
             cancellation_token: CancellationToken::new(),
             event_rx,
@@ -67,6 +194,7 @@ impl Tui {
             tick_rate: 4.0,
             mouse: false,
             paste: false,
+            recorder: None,
         })
     }
 
@@ -90,61 +218,20 @@ impl Tui {
         self
     }
 
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     pub fn start(&mut self) {
         self.cancel(); // Cancel any existing task
         self.cancellation_token = CancellationToken::new();
-        let event_loop = Self::event_loop(
+        self.task = self.events.spawn(
             self.event_tx.clone(),
             self.cancellation_token.clone(),
             self.tick_rate,
             self.frame_rate,
         );
-        self.task = tokio::spawn(async {
-            event_loop.await;
-        });
-    }
-
-    async fn event_loop(
-        event_tx: UnboundedSender<Event>,
-        cancellation_token: CancellationToken,
-        tick_rate: f64,
-        frame_rate: f64,
-    ) {
-        let mut event_stream = EventStream::new();
-        let mut tick_interval = interval(Duration::from_secs_f64(1.0 / tick_rate));
-        let mut render_interval = interval(Duration::from_secs_f64(1.0 / frame_rate));
-
-        // if this fails, then it's likely a bug in the calling code
-        event_tx
-            .send(Event::Init)
-            .expect("failed to send init event");
-        loop {
-            let event = tokio::select! {
-                _ = cancellation_token.cancelled() => {
-                    break;
-                }
-                _ = tick_interval.tick() => Event::Tick,
-                _ = render_interval.tick() => Event::Render,
-                crossterm_event = event_stream.next().fuse() => match crossterm_event {
-                    Some(Ok(event)) => match event {
-                        CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => Event::Key(key),
-                        CrosstermEvent::Mouse(mouse) => Event::Mouse(mouse),
-                        CrosstermEvent::Resize(x, y) => Event::Resize(x, y),
-                        CrosstermEvent::FocusLost => Event::FocusLost,
-                        CrosstermEvent::FocusGained => Event::FocusGained,
-                        CrosstermEvent::Paste(s) => Event::Paste(s),
-                        _ => continue, // ignore other events
-                    }
-                    Some(Err(_)) => Event::Error,
-                    None => break, // the event stream has stopped and will not produce any more events
-                },
-            };
-            if event_tx.send(event).is_err() {
-                // the receiver has been dropped, so there's no point in continuing the loop
-                break;
-            }
-        }
-        cancellation_token.cancel();
     }
 
     pub fn stop(&self) -> Result<()> {
@@ -165,32 +252,16 @@ impl Tui {
     }
 
     pub fn enter(&mut self) -> Result<()> {
-        crossterm::terminal::enable_raw_mode()?;
-        crossterm::execute!(stdout(), EnterAlternateScreen, cursor::Hide)?;
-        if self.mouse {
-            crossterm::execute!(stdout(), EnableMouseCapture)?;
-        }
-        if self.paste {
-            crossterm::execute!(stdout(), EnableBracketedPaste)?;
-        }
+        let (mouse, paste) = (self.mouse, self.paste);
+        self.backend_mut().enter_mode(mouse, paste)?;
         self.start();
         Ok(())
     }
 
     pub fn exit(&mut self) -> Result<()> {
         self.stop()?;
-        if crossterm::terminal::is_raw_mode_enabled()? {
-            self.flush()?;
-            if self.paste {
-                crossterm::execute!(stdout(), DisableBracketedPaste)?;
-            }
-            if self.mouse {
-                crossterm::execute!(stdout(), DisableMouseCapture)?;
-            }
-            crossterm::execute!(stdout(), LeaveAlternateScreen, cursor::Show)?;
-            crossterm::terminal::disable_raw_mode()?;
-        }
-        Ok(())
+        let (mouse, paste) = (self.mouse, self.paste);
+        self.backend_mut().exit_mode(mouse, paste)
     }
 
     pub fn cancel(&self) {
@@ -210,26 +281,77 @@ impl Tui {
     }
 
     pub async fn next_event(&mut self) -> Option<Event> {
-        self.event_rx.recv().await
+        let event = self.event_rx.recv().await?;
+        if let Some(recorder) = &mut self.recorder
+            && let Err(err) = recorder.record(&event)
+        {
+            error!("Stopped recording the session: {err}");
+            self.recorder = None;
+        }
+        Some(event)
     }
 }
 
-impl Deref for Tui {
-    type Target = ratatui::Terminal<Backend<Stdout>>;
+impl<B: Backend + TerminalMode> Deref for Tui<B> {
+    type Target = ratatui::Terminal<B>;
 
     fn deref(&self) -> &Self::Target {
         &self.terminal
     }
 }
 
-impl DerefMut for Tui {
+impl<B: Backend + TerminalMode> DerefMut for Tui<B> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.terminal
     }
 }
 
-impl Drop for Tui {
+impl<B: Backend + TerminalMode> Drop for Tui<B> {
     fn drop(&mut self) {
         self.exit().unwrap();
     }
 }
+
+/// Reads the terminal and sends an event per input, tick and frame.
+async fn event_loop(
+    event_tx: UnboundedSender<Event>,
+    cancellation_token: CancellationToken,
+    tick_rate: f64,
+    frame_rate: f64,
+) {
+    let mut event_stream = EventStream::new();
+    let mut tick_interval = interval(Duration::from_secs_f64(1.0 / tick_rate));
+    let mut render_interval = interval(Duration::from_secs_f64(1.0 / frame_rate));
+
+    // if this fails, then it's likely a bug in the calling code
+    event_tx
+        .send(Event::Init)
+        .expect("failed to send init event");
+    loop {
+        let event = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break;
+            }
+            _ = tick_interval.tick() => Event::Tick,
+            _ = render_interval.tick() => Event::Render,
+            crossterm_event = event_stream.next().fuse() => match crossterm_event {
+                Some(Ok(event)) => match event {
+                    CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => Event::Key(key),
+                    CrosstermEvent::Mouse(mouse) => Event::Mouse(mouse),
+                    CrosstermEvent::Resize(x, y) => Event::Resize(x, y),
+                    CrosstermEvent::FocusLost => Event::FocusLost,
+                    CrosstermEvent::FocusGained => Event::FocusGained,
+                    CrosstermEvent::Paste(s) => Event::Paste(s),
+                    _ => continue, // ignore other events
+                }
+                Some(Err(_)) => Event::Error,
+                None => break, // the event stream has stopped and will not produce any more events
+            },
+        };
+        if event_tx.send(event).is_err() {
+            // the receiver has been dropped, so there's no point in continuing the loop
+            break;
+        }
+    }
+    cancellation_token.cancel();
+}
@@ -0,0 +1,224 @@
+//! Named workspaces: everything needed to pick up an investigation where it was left.
+//!
+//! A workspace is a JSON document with a `version`. Older documents are upgraded by the
+//! migrations in order, fields unknown to this version are ignored so that a document written by
+//! a newer version still loads as far as it is understood.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    action::Bookmark,
+    app::Mode,
+    interpret::{Encoding, Endianness, Kind},
+    layout::{LayoutNode, Panel},
+    recovery,
+};
+
+/// Upgrades a document of version `i + 1` to version `i + 2`, for the migration at index `i`.
+type Migration = fn(&mut Map<String, Value>);
+
+/// Every upgrade since the first version, the last one yields [`VERSION`].
+const MIGRATIONS: &[Migration] = &[];
+
+/// Version of the documents written.
+pub const VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Directory of the named workspaces within the data directory.
+const WORKSPACES_DIR: &str = "workspaces";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    pub version: u32,
+    /// The file the buffer was read from.
+    pub file: Option<PathBuf>,
+    /// The buffer as hex digits, only if it does not match the file.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub input: String,
+    pub kind: Kind,
+    pub endianness: Endianness,
+    pub encoding: Encoding,
+    /// The offset the hex view points at.
+    pub offset: usize,
+    pub bookmarks: Vec<Bookmark>,
+    pub mode: Mode,
+    /// The focused panel of every mode.
+    pub focus: BTreeMap<Mode, Panel>,
+    pub layouts: BTreeMap<Mode, LayoutNode>,
+    /// What every panel had selected, as saved by its component.
+    pub selections: BTreeMap<Panel, Value>,
+}
+
+impl Workspace {
+    /// Whether the document was written by a newer version, parts of it may have been ignored.
+    pub fn is_newer(&self) -> bool {
+        self.version > VERSION
+    }
+}
+
+/// Where the workspace `name` is stored: a name with a `.json` extension or a directory is a
+/// path of its own, e.g. to share it, any other name is kept in the data directory. Either way
+/// the file ends in `.json`, as [`names`] expects.
+pub fn path(data_dir: &Path, name: &str) -> PathBuf {
+    let path = Path::new(name);
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        return path.to_path_buf();
+    }
+    let file = format!("{name}.json");
+    if path
+        .parent()
+        .is_some_and(|parent| !parent.as_os_str().is_empty())
+    {
+        return PathBuf::from(file);
+    }
+    data_dir.join(WORKSPACES_DIR).join(file)
+}
+
+/// Names of the workspaces kept in the data directory, sorted.
+pub fn names(data_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(data_dir.join(WORKSPACES_DIR)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+pub fn save(path: &Path, workspace: &Workspace) -> Result<()> {
+    let workspace = Workspace {
+        version: VERSION,
+        ..workspace.clone()
+    };
+    recovery::write_replacing(path, &(serde_json::to_string_pretty(&workspace)? + "\n"))
+        .wrap_err_with(|| format!("Cannot save the workspace to {}", path.display()))
+}
+
+pub fn load(path: &Path) -> Result<Workspace> {
+    let json = fs::read_to_string(path)
+        .wrap_err_with(|| format!("Cannot open the workspace {}", path.display()))?;
+    parse(&json, MIGRATIONS).wrap_err_with(|| format!("Invalid workspace {}", path.display()))
+}
+
+/// Read a document, upgrading it by the `migrations` that follow its version.
+fn parse(json: &str, migrations: &[Migration]) -> Result<Workspace> {
+    let mut document: Map<String, Value> = serde_json::from_str(json)?;
+    // The first version is the only one that may lack the field.
+    let version = match document.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| eyre!("invalid version {version}"))?,
+    };
+    let latest = migrations.len() as u64 + 1;
+    if version < latest {
+        for migration in &migrations[version as usize - 1..] {
+            migration(&mut document);
+        }
+        document.insert("version".to_string(), latest.into());
+    }
+    Ok(serde_json::from_value(Value::Object(document))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = Workspace {
+            version: VERSION,
+            input: "cafe".to_string(),
+            kind: Kind::U16,
+            offset: 0x10,
+            bookmarks: vec![Bookmark {
+                name: "magic".to_string(),
+                offset: 0,
+            }],
+            mode: Mode::HexView,
+            focus: BTreeMap::from([(Mode::Home, Panel::Bits)]),
+            selections: BTreeMap::from([(Panel::Input, json!("Kind"))]),
+            ..Workspace::default()
+        };
+        let path = path(dir.path(), "firmware");
+        save(&path, &workspace).unwrap();
+        assert_eq!(load(&path).unwrap(), workspace);
+        assert_eq!(names(dir.path()), ["firmware"]);
+        assert!(!path.with_extension("json.partial").exists());
+    }
+
+    #[test]
+    fn test_names_with_an_extension_or_directory_are_paths() {
+        let data_dir = Path::new("/data");
+        assert_eq!(
+            path(data_dir, "firmware"),
+            Path::new("/data/workspaces/firmware.json")
+        );
+        assert_eq!(path(data_dir, "shared.json"), Path::new("shared.json"));
+        assert_eq!(
+            path(data_dir, "team/firmware"),
+            Path::new("team/firmware.json")
+        );
+    }
+
+    #[test]
+    fn test_older_documents_are_migrated() {
+        fn rename_offset(document: &mut Map<String, Value>) {
+            if let Some(offset) = document.remove("top") {
+                document.insert("offset".to_string(), offset);
+            }
+        }
+        fn default_mode(document: &mut Map<String, Value>) {
+            document.entry("mode").or_insert(json!("Inspect"));
+        }
+        let migrations: &[Migration] = &[rename_offset, default_mode];
+
+        let workspace = parse(r#"{ "top": 32 }"#, migrations).unwrap();
+        assert_eq!(workspace.version, 3);
+        assert_eq!(workspace.offset, 32);
+        assert_eq!(workspace.mode, Mode::Inspect);
+        // Only the migrations after the version of the document apply.
+        let workspace = parse(r#"{ "version": 2, "top": 32 }"#, migrations).unwrap();
+        assert_eq!(workspace.offset, 0);
+    }
+
+    #[test]
+    fn test_newer_documents_load_what_is_understood() {
+        let workspace = parse(
+            r#"{ "version": 99, "offset": 8, "annotations": [] }"#,
+            MIGRATIONS,
+        )
+        .unwrap();
+        assert!(workspace.is_newer());
+        assert_eq!(workspace.offset, 8);
+
+        assert!(parse(r#"{ "version": 0 }"#, MIGRATIONS).is_err());
+        assert!(parse("[]", MIGRATIONS).is_err());
+    }
+}